@@ -0,0 +1,417 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use quick_error::quick_error;
+use tracing::{info, warn, Level};
+use tracing_subscriber::EnvFilter;
+
+use netstrat::sources::binance::{
+    errors::ClientError, with_retry, Client, Interval, Kline, Market, PriceKind,
+};
+use netstrat::sources::{clock, MarketDataSource};
+use netstrat::widgets::candles::{export_symbol, Bounds, BoundsSet, Pages, Step};
+
+const USAGE: &str = "Downloads binance klines without GUI.
+
+usage: netstrat-fetch --symbol BTCUSDT --interval 1m --start 2022-01-01 [options]
+
+options:
+    --symbol SYMBOL     symbol to download
+    --interval INTERVAL kline interval in binance notation: 1s, 1m, ..., 1w, 1M
+    --start TIME        range start: millis, 2022-01-01 or 2022-01-01T12:00:00
+    --end TIME          range end, exchange time now if not set
+    --market MARKET     spot or um for USDⓈ-M futures, spot by default
+    --price KIND        last, mark or index price klines, last by default
+    --format FORMAT     csv or json, taken from output extension or csv by default
+    --output PATH       output file, - for stdout; named like GUI export by default
+    --quiet             do not show progress
+    --help              show this message
+
+exit codes:
+    0 klines are written
+    2 invalid arguments
+    3 failed to download klines
+    4 failed to write output
+    5 there are no klines in the range";
+
+const PAGE_LIMIT: usize = 1000;
+const REQUEST_ATTEMPTS: usize = 5;
+const REQUEST_BASE_DELAY: Duration = Duration::from_millis(500);
+
+quick_error! {
+    #[derive(Debug)]
+    enum FetchError {
+        Usage(msg: String) {
+            display("{msg}")
+        }
+        Request(err: ClientError) {
+            from()
+            display("failed to download klines: {err}")
+        }
+        Output(err: io::Error) {
+            from()
+            display("failed to write output: {err}")
+        }
+        Csv(err: csv::Error) {
+            from()
+            display("failed to write csv: {err}")
+        }
+        Json(err: serde_json::Error) {
+            from()
+            display("failed to write json: {err}")
+        }
+        Empty {
+            display("there are no klines in the range")
+        }
+    }
+}
+
+impl FetchError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            FetchError::Usage(_) => 2,
+            FetchError::Request(_) => 3,
+            FetchError::Output(_) | FetchError::Csv(_) | FetchError::Json(_) => 4,
+            FetchError::Empty => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    fn parse(s: &str) -> Option<Format> {
+        match s {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    symbol: String,
+    interval: Interval,
+    start: i64,
+    end: Option<i64>,
+    market: Market,
+    kind: PriceKind,
+    format: Format,
+    output: Option<PathBuf>,
+    quiet: bool,
+}
+
+/// Parses arguments without the program name. Returns None if help is requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Args>, FetchError> {
+    let mut symbol = None;
+    let mut interval = None;
+    let mut start = None;
+    let mut end = None;
+    let mut market = Market::Spot;
+    let mut kind = PriceKind::Last;
+    let mut format = None;
+    let mut output: Option<PathBuf> = None;
+    let mut quiet = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(None);
+        }
+        if arg == "--quiet" {
+            quiet = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| FetchError::Usage(format!("missing value of {arg}")))?;
+        let invalid = || FetchError::Usage(format!("invalid value of {arg}: {value}"));
+
+        match arg.as_str() {
+            "--symbol" => symbol = Some(value.to_uppercase()),
+            "--interval" => interval = Some(Interval::parse(&value).ok_or_else(invalid)?),
+            "--start" => start = Some(parse_time(&value).ok_or_else(invalid)?),
+            "--end" => end = Some(parse_time(&value).ok_or_else(invalid)?),
+            "--market" => {
+                market = Market::ALL
+                    .into_iter()
+                    .find(|m| m.code() == value)
+                    .ok_or_else(invalid)?
+            }
+            "--price" => {
+                kind = match value.as_str() {
+                    "last" => PriceKind::Last,
+                    "mark" => PriceKind::Mark,
+                    "index" => PriceKind::Index,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--format" => format = Some(Format::parse(&value).ok_or_else(invalid)?),
+            "--output" => output = Some(PathBuf::from(value)),
+            _ => return Err(FetchError::Usage(format!("unknown argument: {arg}"))),
+        }
+    }
+
+    let required = |name: &str| FetchError::Usage(format!("{name} is required"));
+    let format = format
+        .or_else(|| {
+            output
+                .as_ref()
+                .and_then(|p| p.extension())
+                .and_then(|e| Format::parse(&e.to_string_lossy()))
+        })
+        .unwrap_or(Format::Csv);
+
+    Ok(Some(Args {
+        symbol: symbol.ok_or_else(|| required("--symbol"))?,
+        interval: interval.ok_or_else(|| required("--interval"))?,
+        start: start.ok_or_else(|| required("--start"))?,
+        end,
+        market,
+        kind,
+        format,
+        output,
+        quiet,
+    }))
+}
+
+/// Parses millis, date or date with time in UTC.
+fn parse_time(s: &str) -> Option<i64> {
+    if let Ok(millis) = s.parse::<i64>() {
+        return Some(millis);
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+        return Some(dt.timestamp_millis());
+    }
+
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_hms(0, 0, 0).timestamp_millis())
+}
+
+/// Downloads klines page by page reporting number of loaded and total pages.
+fn download(
+    source: &dyn MarketDataSource,
+    args: &Args,
+    bounds: &BoundsSet,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<Kline>, FetchError> {
+    let step = Step::from(args.interval);
+    let pages = Pages::new(bounds.clone(), step, PAGE_LIMIT)
+        .ok_or_else(|| FetchError::Usage("invalid time range".to_string()))?;
+
+    let total = pages.len();
+    let mut loaded = 0;
+    let mut klines = vec![];
+
+    progress(loaded, total);
+    for page in pages {
+        let limit = step.count(page.0, page.1);
+        let mut vals = with_retry(REQUEST_ATTEMPTS, REQUEST_BASE_DELAY, || {
+            source.price_kline(args.symbol.clone(), args.interval, args.kind, page.0, limit)
+        })?;
+        klines.append(&mut vals);
+
+        loaded += 1;
+        progress(loaded, total);
+    }
+
+    klines.sort_by_key(|k| k.t_open);
+    klines.dedup_by_key(|k| k.t_open);
+    klines.retain(|k| bounds.contains(k.t_open));
+
+    Ok(klines)
+}
+
+fn write_klines(klines: &[Kline], format: Format, w: impl Write) -> Result<(), FetchError> {
+    match format {
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(w);
+            for k in klines {
+                wtr.serialize(k)?;
+            }
+            wtr.flush()?;
+        }
+        Format::Json => {
+            let mut w = w;
+            serde_json::to_writer(&mut w, klines)?;
+            w.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), FetchError> {
+    let source = match args.market {
+        Market::Spot => Client::default(),
+        Market::UsdFutures => Client::usd_futures(),
+    };
+
+    if let Err(err) = clock::sync(&source) {
+        warn!("failed to sync server clock, using local time: {err}");
+    }
+    let now = clock::now_millis();
+
+    let end = args.end.unwrap_or(now);
+    if end <= args.start {
+        return Err(FetchError::Usage(
+            "--start must be before --end".to_string(),
+        ));
+    }
+
+    // klines in the future do not exist yet, so the range is clipped at exchange time now
+    let bounds = BoundsSet::new(vec![Bounds(args.start, end)]).clip_right(now);
+    let end = match bounds.vals().last() {
+        Some(bounds) => bounds.1,
+        None => return Err(FetchError::Empty),
+    };
+    info!(
+        "downloading {} {} {}: {bounds:?}",
+        args.market.name(),
+        args.symbol,
+        args.interval.as_str()
+    );
+
+    let klines = download(&source, &args, &bounds, |loaded, total| {
+        if !args.quiet {
+            eprint!(
+                "\r{} {}: {loaded}/{total} pages",
+                args.symbol,
+                args.interval.as_str()
+            );
+            if loaded == total {
+                eprintln!();
+            }
+        }
+    })?;
+
+    if klines.is_empty() {
+        return Err(FetchError::Empty);
+    }
+
+    match args.output.as_deref() {
+        Some(path) if path.as_os_str() == "-" => {
+            write_klines(&klines, args.format, io::stdout().lock())?
+        }
+        output => {
+            // default name matches gui export so the file can be imported back
+            let path = output.map(PathBuf::from).unwrap_or_else(|| {
+                PathBuf::from(format!(
+                    "{}_{}_{}_{:?}.{}",
+                    export_symbol(args.market, args.kind, &args.symbol),
+                    args.start / 1000,
+                    end / 1000,
+                    args.interval,
+                    args.format.extension()
+                ))
+            });
+
+            write_klines(&klines, args.format, BufWriter::new(File::create(&path)?))?;
+            if !args.quiet {
+                eprintln!("wrote {} klines to {}", klines.len(), path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn init_logger() {
+    let builder = tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .with_ansi(false)
+        .with_target(false);
+
+    match std::env::var("RUST_LOG") {
+        Ok(_) => builder
+            .with_env_filter(EnvFilter::from_default_env())
+            .init(),
+        Err(_) => builder.with_max_level(Level::WARN).init(),
+    }
+}
+
+fn main() -> ExitCode {
+    init_logger();
+
+    let res = parse_args(std::env::args().skip(1)).and_then(|args| match args {
+        Some(args) => run(args),
+        None => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    });
+
+    match res {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            if let FetchError::Usage(_) = err {
+                eprintln!("\n{USAGE}");
+            }
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod fetch_tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<Option<Args>, FetchError> {
+        parse_args(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args("--symbol btcusdt --interval 1h --start 2022-01-01 --end 1641081600000 --output out.json --market um")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(parsed.symbol, "BTCUSDT");
+        assert_eq!(parsed.interval, Interval::Hour);
+        assert_eq!(parsed.start, 1640995200000);
+        assert_eq!(parsed.end, Some(1641081600000));
+        assert_eq!(parsed.market, Market::UsdFutures);
+        assert_eq!(parsed.format, Format::Json);
+
+        assert!(args("--help").unwrap().is_none());
+        assert_eq!(args("--symbol BTCUSDT").unwrap_err().exit_code(), 2);
+        assert_eq!(
+            args("--symbol BTCUSDT --interval 7m")
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("1000"), Some(1000));
+        assert_eq!(parse_time("2022-01-01T00:01:00"), Some(1640995260000));
+        assert_eq!(parse_time("2022-01-01T00:01:00Z"), Some(1640995260000));
+        assert_eq!(parse_time("yesterday"), None);
+    }
+}
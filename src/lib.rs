@@ -0,0 +1,5 @@
+pub mod netstrat;
+pub mod network;
+pub mod sources;
+pub mod widgets;
+pub mod windows;
@@ -7,19 +7,17 @@ use crossbeam::channel::{unbounded, Receiver, Sender};
 use eframe::{run_native, App, CreationContext, NativeOptions};
 use egui::{Align, CentralPanel, Context, Layout, TopBottomPanel};
 
-use netstrat::Drawer;
+use netstrat::{
+    netstrat::{Bus, Drawer},
+    sources::{
+        binance::{Client, FUTURES_STREAM_URL, STREAM_URL},
+        clock,
+    },
+    windows::{AppWindow, BuffWriter, Compare, Debug, Net, SymbolsGraph, Trades},
+};
 use tracing::{debug, info, Level};
 use tracing_subscriber::EnvFilter;
 
-use crate::{windows::{BuffWriter, Net}, netstrat::Bus};
-use windows::{AppWindow, Debug, SymbolsGraph};
-
-mod netstrat;
-mod network;
-mod sources;
-mod widgets;
-mod windows;
-
 struct TemplateApp {
     windows: Vec<Box<dyn AppWindow>>,
     active_drawer: Option<Arc<Mutex<Box<dyn Drawer>>>>,
@@ -63,16 +61,35 @@ impl TemplateApp {
         info!("starting app");
         let (net_drawer_s, net_drawer_r) = unbounded();
         let (candles_drawer_s, candles_drawer_r) = unbounded();
+        let (trades_drawer_s, trades_drawer_r) = unbounded();
+        let (compare_drawer_s, compare_drawer_r) = unbounded();
 
-        let bus  = Bus::new();
+        let bus = Bus::new();
+        let source = Arc::new(Client::default());
+        let futures_source = Arc::new(Client::usd_futures());
+        clock::spawn_sync(source.clone());
 
         Self {
             windows: vec![
                 Box::new(Net::new(bus, net_drawer_s, false)),
-                Box::new(SymbolsGraph::new(candles_drawer_s, false)),
+                Box::new(SymbolsGraph::new(
+                    source.clone(),
+                    futures_source,
+                    STREAM_URL.to_string(),
+                    FUTURES_STREAM_URL.to_string(),
+                    candles_drawer_s,
+                    false,
+                )),
+                Box::new(Trades::new(source.clone(), trades_drawer_s, false)),
+                Box::new(Compare::new(source, compare_drawer_s, false)),
                 Box::new(Debug::new(buffer_r, false)),
             ],
-            active_drawer_subs: vec![net_drawer_r, candles_drawer_r],
+            active_drawer_subs: vec![
+                net_drawer_r,
+                candles_drawer_r,
+                trades_drawer_r,
+                compare_drawer_r,
+            ],
             active_drawer: None,
         }
     }
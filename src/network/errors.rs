@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use quick_error::quick_error;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum RestError {
+        Reqwest(err: reqwest::Error) {
+            from()
+            display("{}", err)
+        }
+        Timeout(url: String) {
+            display("request timed out: {url}")
+        }
+        MissingFixture(path: PathBuf) {
+            display("no fixture recorded for the request: {}", path.display())
+        }
+        Io(err: std::io::Error) {
+            from()
+            display("failed to access fixture: {}", err)
+        }
+        Fixture(err: serde_json::Error) {
+            from()
+            display("invalid fixture: {}", err)
+        }
+        Http(err: http::Error) {
+            from()
+            display("failed to build response: {}", err)
+        }
+    }
+}
+
+impl RestError {
+    /// Returns true if the same request may succeed later.
+    pub fn retryable(&self) -> bool {
+        match self {
+            RestError::Reqwest(_) | RestError::Timeout(_) => true,
+            RestError::MissingFixture(_)
+            | RestError::Io(_)
+            | RestError::Fixture(_)
+            | RestError::Http(_) => false,
+        }
+    }
+}
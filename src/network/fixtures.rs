@@ -0,0 +1,319 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::blocking::Response;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::errors::RestError;
+
+/// Headers describing the transfer of the recorded body rather than the body itself.
+const SKIPPED_HEADERS: [&str; 4] = [
+    "connection",
+    "content-encoding",
+    "content-length",
+    "transfer-encoding",
+];
+
+/// Body served by the malformed json fault.
+const MALFORMED_BODY: &str = r#"[{"malformed":"#;
+
+/// Recorded request and response pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Request url without query.
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Fixture {
+    pub fn new(url: &str, query: &[(&str, &str)], status: u16, body: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            query: query
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Reads the whole response into a fixture of the request.
+    pub fn record(
+        url: String,
+        query: Vec<(String, String)>,
+        resp: Response,
+    ) -> Result<Self, RestError> {
+        Ok(Self {
+            url,
+            query,
+            status: resp.status().as_u16(),
+            headers: Fixture::recorded_headers(resp.headers()),
+            body: resp.text()?,
+        })
+    }
+
+    /// Async version of record.
+    pub async fn record_async(
+        url: String,
+        query: Vec<(String, String)>,
+        resp: reqwest::Response,
+    ) -> Result<Self, RestError> {
+        Ok(Self {
+            url,
+            query,
+            status: resp.status().as_u16(),
+            headers: Fixture::recorded_headers(resp.headers()),
+            body: resp.text().await?,
+        })
+    }
+
+    fn recorded_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect()
+    }
+
+    /// Name of the fixture file. Requests with the same url and query share it.
+    pub fn file_name(url: &str, query: &[(String, String)]) -> String {
+        let mut query = query.to_vec();
+        query.sort();
+
+        let key = query.iter().fold(url.to_string(), |mut key, (k, v)| {
+            key.push_str(&format!("&{k}={v}"));
+            key
+        });
+
+        let prefix: String = url
+            .split("://")
+            .last()
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        format!("{prefix}_{:016x}.json", fnv1a(key.as_bytes()))
+    }
+
+    pub fn load(dir: &Path, url: &str, query: &[(String, String)]) -> Result<Self, RestError> {
+        let path = dir.join(Self::file_name(url, query));
+        if !path.exists() {
+            return Err(RestError::MissingFixture(path));
+        }
+
+        let f = File::open(&path)?;
+        Ok(serde_json::from_reader(BufReader::new(f))?)
+    }
+
+    /// Writes the fixture to the dir replacing previous recording of the same request.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, RestError> {
+        fs::create_dir_all(dir)?;
+
+        let path = dir.join(Self::file_name(&self.url, &self.query));
+        serde_json::to_writer_pretty(BufWriter::new(File::create(&path)?), self)?;
+
+        Ok(path)
+    }
+
+    pub fn into_response(self) -> Result<Response, RestError> {
+        Ok(Response::from(self.into_http()?))
+    }
+
+    pub fn into_async_response(self) -> Result<reqwest::Response, RestError> {
+        Ok(reqwest::Response::from(self.into_http()?))
+    }
+
+    fn into_http(self) -> Result<http::Response<String>, RestError> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        Ok(builder.body(self.body)?)
+    }
+}
+
+/// Stable hash keeping fixture names the same across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Error injected by replay instead of the recorded response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Request fails as if the server did not answer in time.
+    Timeout,
+    /// 429 response with optional Retry-After seconds.
+    TooManyRequests(Option<u64>),
+    /// 200 response with a body which is not valid json.
+    MalformedJson,
+    /// Response with the status and empty json body.
+    Status(u16),
+}
+
+impl Fault {
+    fn fixture(&self, url: &str) -> Result<Fixture, RestError> {
+        let fixture = match self {
+            Fault::Timeout => return Err(RestError::Timeout(url.to_string())),
+            Fault::TooManyRequests(retry_after) => {
+                let fixture = Fixture::new(url, &[], 429, "{}");
+                match retry_after {
+                    Some(secs) => fixture.with_header("retry-after", &secs.to_string()),
+                    None => fixture,
+                }
+            }
+            Fault::MalformedJson => Fixture::new(url, &[], 200, MALFORMED_BODY),
+            Fault::Status(code) => Fixture::new(url, &[], *code, "{}"),
+        };
+
+        Ok(fixture)
+    }
+}
+
+#[derive(Debug)]
+struct Injection {
+    url_part: String,
+    fault: Fault,
+    remaining: usize,
+}
+
+/// Serves recorded fixtures without network.
+///
+/// Faults are injected in the order they were added and each one is used up after the given
+/// number of matching requests, after which the fixture is served again.
+#[derive(Debug)]
+pub struct Replayer {
+    dir: PathBuf,
+    faults: Mutex<Vec<Injection>>,
+}
+
+impl Replayer {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            faults: Default::default(),
+        }
+    }
+
+    pub fn with_fault(self, url_part: &str, fault: Fault, times: usize) -> Self {
+        self.inject(url_part, fault, times);
+        self
+    }
+
+    /// Makes the next times requests with url containing url_part fail with the fault.
+    pub fn inject(&self, url_part: &str, fault: Fault, times: usize) {
+        self.faults.lock().unwrap().push(Injection {
+            url_part: url_part.to_string(),
+            fault,
+            remaining: times,
+        });
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn serve(&self, url: &str, query: &[(String, String)]) -> Result<Response, RestError> {
+        self.fixture(url, query)?.into_response()
+    }
+
+    pub fn serve_async(
+        &self,
+        url: &str,
+        query: &[(String, String)],
+    ) -> Result<reqwest::Response, RestError> {
+        self.fixture(url, query)?.into_async_response()
+    }
+
+    /// Returns injected fault or the recorded fixture of the request.
+    fn fixture(&self, url: &str, query: &[(String, String)]) -> Result<Fixture, RestError> {
+        if let Some(fault) = self.take_fault(url) {
+            debug!("injecting fault: {fault:?}; url: {url}");
+            return fault.fixture(url);
+        }
+
+        Fixture::load(&self.dir, url, query)
+    }
+
+    fn take_fault(&self, url: &str) -> Option<Fault> {
+        let mut faults = self.faults.lock().unwrap();
+        let injection = faults
+            .iter_mut()
+            .find(|i| i.remaining > 0 && url.contains(i.url_part.as_str()))?;
+        injection.remaining -= 1;
+
+        Some(injection.fault)
+    }
+}
+
+#[cfg(test)]
+mod fixtures_tests {
+    use super::*;
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_name() {
+        let url = "https://api.binance.com/api/v3/klines";
+        let name = Fixture::file_name(url, &query(&[("symbol", "BTCUSDT"), ("limit", "1")]));
+
+        assert!(name.starts_with("api_binance_com_api_v3_klines_"));
+        assert_eq!(
+            name,
+            Fixture::file_name(url, &query(&[("limit", "1"), ("symbol", "BTCUSDT")]))
+        );
+        assert_ne!(
+            name,
+            Fixture::file_name(url, &query(&[("symbol", "BTCUSDT"), ("limit", "2")]))
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let url = "http://localhost/api/v3/time";
+        Fixture::new(url, &[("a", "1")], 200, r#"{"serverTime":1}"#)
+            .with_header("x-mbx-used-weight-1m", "5")
+            .save(&dir)
+            .unwrap();
+
+        let replayer = Replayer::new(dir.clone()).with_fault("/time", Fault::Status(503), 1);
+
+        let resp = replayer.serve(url, &query(&[("a", "1")])).unwrap();
+        assert_eq!(resp.status().as_u16(), 503);
+
+        let resp = replayer.serve(url, &query(&[("a", "1")])).unwrap();
+        assert_eq!(resp.status().as_u16(), 200);
+        assert_eq!(resp.headers()["x-mbx-used-weight-1m"], "5");
+        assert_eq!(resp.text().unwrap(), r#"{"serverTime":1}"#);
+
+        assert!(matches!(
+            replayer.serve(url, &query(&[("a", "2")])),
+            Err(RestError::MissingFixture(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1 +1,3 @@
+pub mod errors;
+pub mod fixtures;
 pub mod rest;
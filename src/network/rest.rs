@@ -1,18 +1,68 @@
-use tracing::{trace, debug};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tracing::{debug, info, trace};
+
+use super::errors::RestError;
+use super::fixtures::{Fixture, Replayer};
+
+/// Directory to record fixtures of all requests to.
+pub const ENV_RECORD_DIR: &str = "NETSTRAT_RECORD_DIR";
+/// Directory to replay fixtures from instead of sending requests.
+pub const ENV_REPLAY_DIR: &str = "NETSTRAT_REPLAY_DIR";
+
+#[derive(Clone, Debug, Default)]
+pub enum Mode {
+    #[default]
+    Live,
+    /// Sends requests and saves request/response pairs as fixtures to the dir.
+    Record(PathBuf),
+    /// Serves recorded fixtures without network.
+    Replay(Arc<Replayer>),
+}
 
 #[derive(Clone, Debug)]
 pub struct Rest {
     c: reqwest::blocking::Client,
+    /// Client of async requests which are aborted when their future is dropped.
+    ac: reqwest::Client,
+    mode: Mode,
 }
 
 impl Rest {
     pub fn new() -> Rest {
         Rest {
             c: reqwest::blocking::Client::new(),
+            ac: reqwest::Client::new(),
+            mode: Mode::Live,
+        }
+    }
+
+    /// Creates live client unless record or replay dir is set in env.
+    pub fn from_env() -> Rest {
+        if let Ok(dir) = std::env::var(ENV_REPLAY_DIR) {
+            info!("replaying requests from fixtures: {dir}");
+            return Rest::new().with_mode(Mode::Replay(Arc::new(Replayer::new(dir.into()))));
+        }
+
+        if let Ok(dir) = std::env::var(ENV_RECORD_DIR) {
+            info!("recording requests to fixtures: {dir}");
+            return Rest::new().with_mode(Mode::Record(dir.into()));
         }
+
+        Rest::new()
+    }
+
+    pub fn with_mode(mut self, mode: Mode) -> Rest {
+        self.mode = mode;
+        self
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.mode
     }
 
-    pub fn get(&self, url: &str) -> Result<reqwest::blocking::Response, reqwest::Error> {
+    pub fn get(&self, url: &str) -> Result<reqwest::blocking::Response, RestError> {
         let req = self.c.get(url);
 
         self.execute_request(req)
@@ -22,17 +72,92 @@ impl Rest {
         &self,
         url: &str,
         params: &[(&str, &str)],
-    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+    ) -> Result<reqwest::blocking::Response, RestError> {
         let req = self.c.get(url).query(params);
 
         self.execute_request(req)
     }
 
+    pub fn get_with_params_and_headers(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+        headers: &[(&str, &str)],
+    ) -> Result<reqwest::blocking::Response, RestError> {
+        let mut req = self.c.get(url).query(params);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+
+        self.execute_request(req)
+    }
+
+    /// Async version of get_with_params. Dropping the future aborts the request.
+    pub async fn get_with_params_async(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+    ) -> Result<reqwest::Response, RestError> {
+        let req_builded = self.ac.get(url).query(params).build()?;
+        let (url, query) = Rest::fixture_key(req_builded.url());
+
+        if let Mode::Replay(replayer) = &self.mode {
+            debug!(
+                "replaying request: url: {:?}; query: {query:?}.",
+                url.as_str()
+            );
+            return replayer.serve_async(url.as_str(), &query);
+        }
+
+        debug!(
+            "sending request: method: {:?}; url: {:?}; headers: {:?}.",
+            req_builded.method(),
+            req_builded.url().as_str(),
+            req_builded.headers(),
+        );
+
+        let resp = self.ac.execute(req_builded).await?;
+        match &self.mode {
+            Mode::Record(dir) => {
+                let fixture = Fixture::record_async(url.to_string(), query, resp).await?;
+                let path = fixture.save(dir)?;
+                trace!("recorded fixture: {}", path.display());
+
+                fixture.into_async_response()
+            }
+            _ => Ok(resp),
+        }
+    }
+
+    /// Splits url to the url without query and query params.
+    ///
+    /// Fixtures keep query separately so its params can be compared in any order.
+    fn fixture_key(url: &reqwest::Url) -> (reqwest::Url, Vec<(String, String)>) {
+        let mut url = url.clone();
+        let query = url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect::<Vec<_>>();
+        url.set_query(None);
+
+        (url, query)
+    }
+
     fn execute_request(
         &self,
         req: reqwest::blocking::RequestBuilder,
-    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+    ) -> Result<reqwest::blocking::Response, RestError> {
         let req_builded = req.build()?;
+        let (url, query) = Rest::fixture_key(req_builded.url());
+
+        if let Mode::Replay(replayer) = &self.mode {
+            debug!(
+                "replaying request: url: {:?}; query: {query:?}.",
+                url.as_str()
+            );
+            return replayer.serve(url.as_str(), &query);
+        }
+
         debug!(
             "sending request: method: {:?}; url: {:?}; headers: {:?}; body: {:?}.",
             req_builded.method(),
@@ -41,6 +166,16 @@ impl Rest {
             req_builded.body(),
         );
 
-        self.c.execute(req_builded)
+        let resp = self.c.execute(req_builded)?;
+        match &self.mode {
+            Mode::Record(dir) => {
+                let fixture = Fixture::record(url.to_string(), query, resp)?;
+                let path = fixture.save(dir)?;
+                trace!("recorded fixture: {}", path.display());
+
+                fixture.into_response()
+            }
+            _ => Ok(resp),
+        }
     }
 }
@@ -0,0 +1,249 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossbeam::channel::Sender;
+use lazy_static::lazy_static;
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error};
+
+use super::binance::{errors::ClientError, with_retry_async, Interval, Kline, PriceKind};
+use super::{MarketDataSource, SourceFuture};
+
+const REQUEST_ATTEMPTS: usize = 5;
+const REQUEST_BASE_DELAY: Duration = Duration::from_millis(500);
+
+lazy_static! {
+    /// Runtime used when client is created outside of the app runtime, e.g. in tests.
+    static ref FALLBACK_RUNTIME: Runtime = Runtime::new().unwrap();
+}
+
+/// Result of a request tagged with id of the download it belongs to.
+#[derive(Debug)]
+pub struct Tagged<T> {
+    pub request_id: u64,
+    pub result: T,
+}
+
+/// Handle of a started download. Cancelling it drops all its pending requests.
+#[derive(Debug)]
+pub struct Download {
+    id: u64,
+    token: CancellationToken,
+}
+
+impl Download {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn cancel(&self) {
+        debug!("cancelling download: {}", self.id);
+        self.token.cancel();
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+/// Runs market data requests on the tokio runtime.
+///
+/// Number of requests in flight is bounded by max_concurrent.
+/// Clones share the same limit and request ids.
+#[derive(Clone)]
+pub struct AsyncClient {
+    source: Arc<dyn MarketDataSource>,
+    permits: Arc<Semaphore>,
+    next_id: Arc<AtomicU64>,
+    runtime: Handle,
+}
+
+impl AsyncClient {
+    pub fn new(source: Arc<dyn MarketDataSource>, max_concurrent: usize) -> Self {
+        let runtime = Handle::try_current().unwrap_or_else(|_| FALLBACK_RUNTIME.handle().clone());
+
+        Self {
+            source,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            next_id: Arc::new(AtomicU64::new(1)),
+            runtime,
+        }
+    }
+
+    /// Creates client requesting another source which shares the limit and request ids.
+    pub fn with_source(&self, source: Arc<dyn MarketDataSource>) -> Self {
+        Self {
+            source,
+            ..self.clone()
+        }
+    }
+
+    /// Starts new download with unique request id.
+    pub fn start_download(&self) -> Download {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        debug!("starting download: {id}");
+
+        Download {
+            id,
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Requests klines page sending the tagged result to the sender.
+    ///
+    /// Nothing is sent if the download is cancelled before the page arrives.
+    pub fn kline(
+        &self,
+        download: &Download,
+        symbol: String,
+        interval: Interval,
+        start_time: i64,
+        limit: usize,
+        sender: Sender<Tagged<Result<Vec<Kline>, ClientError>>>,
+    ) {
+        self.request(download, sender, move |source| {
+            source.price_kline_async(symbol.clone(), interval, PriceKind::Last, start_time, limit)
+        });
+    }
+
+    /// Runs source request with retries and bounded concurrency
+    /// sending the tagged result to the sender.
+    ///
+    /// Request is aborted as soon as the download is cancelled.
+    /// Call may be repeated on retries so it must not consume captured values.
+    pub fn request<T, F>(
+        &self,
+        download: &Download,
+        sender: Sender<Tagged<Result<T, ClientError>>>,
+        f: F,
+    ) where
+        T: Send + 'static,
+        F: Fn(Arc<dyn MarketDataSource>) -> SourceFuture<T> + Send + Sync + 'static,
+    {
+        let request_id = download.id;
+        let token = download.token.clone();
+        let permits = self.permits.clone();
+        let source = self.source.clone();
+
+        self.runtime.spawn(async move {
+            let request = async {
+                let _permit = permits.acquire().await.unwrap();
+
+                with_retry_async(REQUEST_ATTEMPTS, REQUEST_BASE_DELAY, || f(source.clone())).await
+            };
+
+            let result = tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("dropping request of cancelled download: {request_id}");
+                    return;
+                }
+                result = request => result,
+            };
+
+            if let Err(err) = sender.send(Tagged { request_id, result }) {
+                error!("failed to send request result: {err}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod async_client_tests {
+    use std::time::Duration;
+
+    use crossbeam::channel::unbounded;
+
+    use super::*;
+    use crate::sources::FakeSource;
+
+    /// Source answering after a delay on the blocking pool.
+    fn slow_source() -> FakeSource {
+        FakeSource::default().with_kline(|start_time, _| {
+            std::thread::sleep(Duration::from_millis(50));
+
+            Ok(vec![Kline {
+                t_open: start_time,
+                ..Default::default()
+            }])
+        })
+    }
+
+    /// Source answering after a delay without blocking the runtime.
+    fn async_source(finished: Arc<AtomicU64>) -> FakeSource {
+        FakeSource::default().with_kline_async(move |start_time, _| {
+            let finished = finished.clone();
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                finished.fetch_add(1, Ordering::Relaxed);
+
+                Ok(vec![Kline {
+                    t_open: start_time,
+                    ..Default::default()
+                }])
+            })
+        })
+    }
+
+    #[test]
+    fn test_cancel_aborts_request() {
+        let finished = Arc::new(AtomicU64::new(0));
+        let client = AsyncClient::new(Arc::new(async_source(finished.clone())), 2);
+        let (s, r) = unbounded();
+
+        let old = client.start_download();
+        client.kline(
+            &old,
+            "ETHBTC".to_string(),
+            Interval::Minute,
+            0,
+            1,
+            s.clone(),
+        );
+        old.cancel();
+
+        let new = client.start_download();
+        client.kline(&new, "ETHBTC".to_string(), Interval::Minute, 1, 1, s);
+
+        let tagged = r.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(tagged.request_id, new.id());
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(finished.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let client = AsyncClient::new(Arc::new(slow_source()), 1);
+        let (s, r) = unbounded();
+
+        let old = client.start_download();
+        client.kline(
+            &old,
+            "ETHBTC".to_string(),
+            Interval::Minute,
+            0,
+            1,
+            s.clone(),
+        );
+        client.kline(
+            &old,
+            "ETHBTC".to_string(),
+            Interval::Minute,
+            1,
+            1,
+            s.clone(),
+        );
+        old.cancel();
+
+        let new = client.start_download();
+        assert_ne!(old.id(), new.id());
+        client.kline(&new, "ETHBTC".to_string(), Interval::Minute, 2, 1, s);
+
+        let tagged = r.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(tagged.request_id, new.id());
+        assert_eq!(tagged.result.unwrap()[0].t_open, 2);
+        assert!(r.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}
@@ -1,27 +1,96 @@
 use std::cmp::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
-use reqwest::header::HeaderValue;
-use serde::{Deserialize, Serialize};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json;
 use tokio::task::spawn_blocking;
 use tracing::debug;
 
 use crate::network::rest::Rest;
 use crate::sources::binance::interval::Interval;
+use crate::sources::{MarketDataSource, SourceFuture};
 
+use super::depth::{Depth, DepthData};
 use super::errors::ClientError;
+use super::filters::{self, Filter, LotSize, OrderType, PriceFilter};
+use super::futures::{
+    FundingRate, FundingRateData, OpenInterest, OpenInterestData, OPEN_INTEREST_PERIODS,
+};
+use super::market::{Market, PriceKind};
+use super::rate_limiter::RateLimiter;
+use super::ticker::{Ticker24h, Ticker24hData};
+use super::trades::{AggTrade, AggTradeData, Trade, TradeData};
 
 const HEADER_REQ_WEIGHT: &str = "x-mbx-used-weight-1m";
 const HEADER_RETRY_AFTER: &str = "Retry-After";
+const HEADER_API_KEY: &str = "X-MBX-APIKEY";
 
-#[derive(Clone, Debug, Default)]
-pub struct Client {}
-
-const BASE_URL: &str = "https://api.binance.com";
+pub const BASE_URL: &str = "https://api.binance.com";
 const PATH_KLINE: &str = "/api/v3/klines";
 const PATH_INFO: &str = "/api/v3/exchangeInfo";
+const PATH_TIME: &str = "/api/v3/time";
+const PATH_DEPTH: &str = "/api/v3/depth";
+const PATH_TICKER_24H: &str = "/api/v3/ticker/24hr";
+const PATH_AGG_TRADES: &str = "/api/v3/aggTrades";
+const PATH_HISTORICAL_TRADES: &str = "/api/v3/historicalTrades";
+
+pub const FUTURES_BASE_URL: &str = "https://fapi.binance.com";
+const PATH_FUTURES_KLINE: &str = "/fapi/v1/klines";
+const PATH_FUTURES_MARK_KLINE: &str = "/fapi/v1/markPriceKlines";
+const PATH_FUTURES_INDEX_KLINE: &str = "/fapi/v1/indexPriceKlines";
+const PATH_FUTURES_INFO: &str = "/fapi/v1/exchangeInfo";
+const PATH_FUTURES_TIME: &str = "/fapi/v1/time";
+const PATH_FUTURES_DEPTH: &str = "/fapi/v1/depth";
+const PATH_FUTURES_TICKER_24H: &str = "/fapi/v1/ticker/24hr";
+const PATH_FUTURES_AGG_TRADES: &str = "/fapi/v1/aggTrades";
+const PATH_FUTURES_HISTORICAL_TRADES: &str = "/fapi/v1/historicalTrades";
+const PATH_FUTURES_FUNDING_RATE: &str = "/fapi/v1/fundingRate";
+const PATH_FUTURES_OPEN_INTEREST_HIST: &str = "/futures/data/openInterestHist";
+
+/// Request weight limit per minute for the ip.
+pub const WEIGHT_LIMIT: usize = 1200;
+const WEIGHT_KLINE: usize = 2;
+const WEIGHT_INFO: usize = 20;
+const WEIGHT_TIME: usize = 1;
+/// Weight of 24h ticker request for all symbols.
+const WEIGHT_TICKER_24H: usize = 40;
+const WEIGHT_AGG_TRADES: usize = 2;
+const WEIGHT_HISTORICAL_TRADES: usize = 10;
+const WEIGHT_FUNDING_RATE: usize = 1;
+const WEIGHT_OPEN_INTEREST_HIST: usize = 1;
+
+/// Url and query params of a request.
+type Request = (String, Vec<(&'static str, String)>);
+
+/// Binance REST api client.
+///
+/// Clones share the same rate limiter.
+#[derive(Clone, Debug)]
+pub struct Client {
+    base_url: String,
+    market: Market,
+    api_key: Option<String>,
+    limiter: Arc<RateLimiter>,
+    rest: Rest,
+}
 
-#[derive(Debug, Deserialize, Default)]
+impl Default for Client {
+    /// Creates spot client which records or replays requests if env asks for it.
+    fn default() -> Self {
+        Self::new(BASE_URL.to_string()).with_rest(Rest::from_env())
+    }
+}
+
+#[derive(Deserialize)]
+struct ServerTime {
+    #[serde(rename = "serverTime")]
+    server_time: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Info {
     pub symbols: Vec<Symbol>,
 }
@@ -41,7 +110,7 @@ struct KlineData(
     String, // Taker buy quote asset volume
     String, // Ignore
 );
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Symbol {
     pub symbol: String,
     pub status: String,
@@ -58,73 +127,152 @@ pub struct Symbol {
     #[serde(rename = "quotePrecision")]
     quote_precision: usize,
 
-    #[serde(rename = "quoteAssetPrecision")]
+    /// Futures symbols have price precision instead.
+    #[serde(rename = "quoteAssetPrecision", alias = "pricePrecision", default)]
     quote_asset_precision: usize,
 
-    #[serde(rename = "baseCommissionPrecision")]
+    #[serde(rename = "baseCommissionPrecision", default)]
     base_commission_precision: usize,
 
-    #[serde(rename = "quoteCommissionPrecision")]
+    #[serde(rename = "quoteCommissionPrecision", default)]
     quote_commission_precision: usize,
 
-    #[serde(rename = "icebergAllowed")]
+    #[serde(rename = "icebergAllowed", default)]
     iceberg_allowed: bool,
 
-    #[serde(rename = "ocoAllowed")]
+    #[serde(rename = "ocoAllowed", default)]
     oco_allowed: bool,
 
-    #[serde(rename = "quoteOrderQtyMarketAllowed")]
+    #[serde(rename = "quoteOrderQtyMarketAllowed", default)]
     quote_order_qty_market_allowed: bool,
 
-    #[serde(rename = "allowTrailingStop")]
+    #[serde(rename = "allowTrailingStop", default)]
     allow_trailing_stop: bool,
 
-    #[serde(rename = "isSpotTradingAllowed")]
+    #[serde(rename = "isSpotTradingAllowed", default)]
     is_spot_trading_allowed: bool,
 
-    #[serde(rename = "isMarginTradingAllowed")]
+    #[serde(rename = "isMarginTradingAllowed", default)]
     is_margin_trading_allowed: bool,
+
+    #[serde(rename = "orderTypes", default)]
+    order_types: Vec<OrderType>,
+
+    #[serde(default, deserialize_with = "filters::deserialize_lenient")]
+    filters: Vec<Filter>,
+
+    #[serde(default)]
+    market: Market,
 }
 
 impl Symbol {
     pub fn active(&self) -> bool {
         self.status == "TRADING"
     }
+
+    pub fn base_asset_precision(&self) -> usize {
+        self.base_asset_precision
+    }
+
+    pub fn quote_asset_precision(&self) -> usize {
+        self.quote_asset_precision
+    }
+
+    pub fn market(&self) -> Market {
+        self.market
+    }
+
+    pub fn base_asset(&self) -> &str {
+        &self.base_asset
+    }
+
+    pub fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    pub fn order_types(&self) -> &[OrderType] {
+        &self.order_types
+    }
+
+    pub fn filters(&self) -> &[Filter] {
+        &self.filters
+    }
+
+    pub fn price_filter(&self) -> Option<PriceFilter> {
+        self.filters.iter().find_map(|f| match f {
+            Filter::Price(p) => Some(*p),
+            _ => None,
+        })
+    }
+
+    pub fn lot_size(&self) -> Option<LotSize> {
+        self.filters.iter().find_map(|f| match f {
+            Filter::LotSize(l) => Some(*l),
+            _ => None,
+        })
+    }
+
+    /// Rounds price to the tick size of the symbol like the exchange does.
+    pub fn round_price(&self, price: f64) -> f64 {
+        match self.price_filter() {
+            Some(filter) => filter.round(price),
+            None => price,
+        }
+    }
+
+    /// Rounds quantity down to the lot step size of the symbol.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        match self.lot_size() {
+            Some(lot) => lot.round(qty),
+            None => qty,
+        }
+    }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize)]
+#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Kline {
     pub t_open: i64,
-    pub open: f32,
-    pub high: f32,
-    pub low: f32,
-    pub close: f32,
-    pub volume: f32,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
     pub t_close: i64,
-    pub quote_asset_volume: f32,
+    pub quote_asset_volume: f64,
     pub number_of_trades: i64,
-    pub taker_buy_base_asset_volume: f32,
-    pub taker_buy_quote_asset_volume: f32,
+    pub taker_buy_base_asset_volume: f64,
+    pub taker_buy_quote_asset_volume: f64,
 }
 
 impl Kline {
-    fn from_kline_data(data: KlineData) -> Self {
-        Kline {
+    fn from_kline_data(data: KlineData) -> Result<Self, ClientError> {
+        Ok(Kline {
             t_open: data.0,
-            open: data.1.parse::<f32>().unwrap(),
-            high: data.2.parse::<f32>().unwrap(),
-            low: data.3.parse::<f32>().unwrap(),
-            close: data.4.parse::<f32>().unwrap(),
-            volume: data.5.parse::<f32>().unwrap(),
+            open: parse_number(&data.1)?,
+            high: parse_number(&data.2)?,
+            low: parse_number(&data.3)?,
+            close: parse_number(&data.4)?,
+            volume: parse_number(&data.5)?,
             t_close: data.6,
-            quote_asset_volume: data.7.parse::<f32>().unwrap(),
+            quote_asset_volume: parse_number(&data.7)?,
             number_of_trades: data.8,
-            taker_buy_base_asset_volume: data.9.parse::<f32>().unwrap(),
-            taker_buy_quote_asset_volume: data.10.parse::<f32>().unwrap(),
-        }
+            taker_buy_base_asset_volume: parse_number(&data.9)?,
+            taker_buy_quote_asset_volume: parse_number(&data.10)?,
+        })
     }
 }
 
+/// Parses decimal string returned by binance.
+///
+/// f64 keeps all significant digits binance uses so values survive formatting
+/// with the symbol precision unchanged.
+pub(super) fn parse_number(s: &str) -> Result<f64, ClientError> {
+    // mark price klines have trailing spaces in unused volume fields
+    s.trim()
+        .parse::<f64>()
+        .map_err(|_| ClientError::Parse(s.to_string()))
+}
+
 impl Ord for Kline {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.close < other.close {
@@ -148,61 +296,806 @@ impl PartialOrd for Kline {
 }
 
 impl Client {
-    pub fn kline(
+    /// Creates client sending live requests to base_url, e.g. testnet or local mock server.
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            market: Market::Spot,
+            api_key: None,
+            limiter: Arc::new(RateLimiter::new(WEIGHT_LIMIT)),
+            rest: Rest::new(),
+        }
+    }
+
+    /// Creates client of USDⓈ-M futures market which, like default one, records or replays
+    /// requests if env asks for it.
+    pub fn usd_futures() -> Self {
+        Self::new(FUTURES_BASE_URL.to_string())
+            .with_market(Market::UsdFutures)
+            .with_rest(Rest::from_env())
+    }
+
+    /// Sets market which endpoints are requested. Base url must point to the same market.
+    pub fn with_market(mut self, market: Market) -> Self {
+        self.market = market;
+        self
+    }
+
+    /// Sets api key required by market data endpoints like historical trades.
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Sets http layer, e.g. replaying recorded fixtures in tests.
+    pub fn with_rest(mut self, rest: Rest) -> Self {
+        self.rest = rest;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn market(&self) -> Market {
+        self.market
+    }
+
+    /// Url of the endpoint on the client market.
+    fn url(&self, spot_path: &str, futures_path: &str) -> String {
+        match self.market {
+            Market::Spot => format!("{}{}", self.base_url, spot_path),
+            Market::UsdFutures => format!("{}{}", self.base_url, futures_path),
+        }
+    }
+
+    fn futures_only(&self, what: &str) -> Result<(), ClientError> {
+        match self.market {
+            Market::UsdFutures => Ok(()),
+            Market::Spot => Err(ClientError::Unsupported(what.to_string())),
+        }
+    }
+
+    fn get_klines(&self, url: &str, params: &[(&str, &str)]) -> Result<Vec<Kline>, ClientError> {
+        self.limiter.acquire(WEIGHT_KLINE);
+        let resp = self.rest.get_with_params(url, params)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<Vec<KlineData>>(json_str)?;
+
+        res.into_iter().map(Kline::from_kline_data).collect()
+    }
+
+    /// Async request of the json resource which is aborted when the future is dropped.
+    async fn get_async<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        params: &[(&str, String)],
+        weight: usize,
+    ) -> Result<T, ClientError> {
+        let params = params
+            .iter()
+            .map(|(k, v)| (*k, v.as_str()))
+            .collect::<Vec<_>>();
+
+        self.limiter.acquire_async(weight).await;
+        let resp = self.rest.get_with_params_async(url, &params).await?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text().await?;
+        Ok(serde_json::from_str::<T>(json_str)?)
+    }
+
+    /// Url and params of klines request of the price kind.
+    fn price_kline_request(
+        &self,
         symbol: String,
         interval: Interval,
+        kind: PriceKind,
         start_time: i64,
         limit: usize,
-    ) -> Result<Vec<Kline>, ClientError> {
-        let url = format!("{}{}", BASE_URL, PATH_KLINE);
-        let params = &[
-            ("symbol", symbol.as_str()),
-            ("interval", interval.as_str()),
-            ("startTime", &start_time.to_string()),
-            ("limit", &limit.to_string()),
+    ) -> Result<Request, ClientError> {
+        let (url, symbol_param) = match kind {
+            PriceKind::Last => (self.url(PATH_KLINE, PATH_FUTURES_KLINE), "symbol"),
+            PriceKind::Mark => {
+                self.futures_only(kind.name())?;
+                (self.url("", PATH_FUTURES_MARK_KLINE), "symbol")
+            }
+            PriceKind::Index => {
+                self.futures_only(kind.name())?;
+                (self.url("", PATH_FUTURES_INDEX_KLINE), "pair")
+            }
+        };
+
+        // index is calculated for the pair, delivery contracts add expiration date to it
+        let symbol = match kind {
+            PriceKind::Index => symbol.split('_').next().unwrap_or_default().to_string(),
+            _ => symbol,
+        };
+
+        let params = vec![
+            (symbol_param, symbol),
+            ("interval", interval.as_str().to_string()),
+            ("startTime", start_time.to_string()),
+            ("limit", limit.to_string()),
+        ];
+
+        Ok((url, params))
+    }
+
+    /// Url and params of funding rate request.
+    fn funding_rate_request(
+        &self,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> Result<Request, ClientError> {
+        self.futures_only("funding rate")?;
+
+        let url = self.url("", PATH_FUTURES_FUNDING_RATE);
+        let params = vec![
+            ("symbol", symbol),
+            ("startTime", start_time.to_string()),
+            ("endTime", end_time.to_string()),
+            ("limit", limit.to_string()),
+        ];
+
+        Ok((url, params))
+    }
+
+    /// Url and params of open interest statistics request.
+    fn open_interest_hist_request(
+        &self,
+        symbol: String,
+        period: Interval,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> Result<Request, ClientError> {
+        self.futures_only("open interest")?;
+        if !OPEN_INTEREST_PERIODS.contains(&period) {
+            return Err(ClientError::Unsupported(format!(
+                "open interest period {}",
+                period.as_str()
+            )));
+        }
+
+        let url = self.url("", PATH_FUTURES_OPEN_INTEREST_HIST);
+        let params = vec![
+            ("symbol", symbol),
+            ("period", period.as_str().to_string()),
+            ("startTime", start_time.to_string()),
+            ("endTime", end_time.to_string()),
+            ("limit", limit.to_string()),
         ];
 
-        let resp = Rest::new().get_with_params(&url, params)?;
+        Ok((url, params))
+    }
+
+    /// Returns up to limit trades starting from from_id or the most recent trades.
+    ///
+    /// Binance requires api key for this endpoint, see with_api_key.
+    pub fn historical_trades(
+        &self,
+        symbol: String,
+        from_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Trade>, ClientError> {
+        let url = self.url(PATH_HISTORICAL_TRADES, PATH_FUTURES_HISTORICAL_TRADES);
+        let limit = limit.to_string();
+        let from_id = from_id.map(|id| id.to_string());
+
+        let mut params = vec![("symbol", symbol.as_str()), ("limit", limit.as_str())];
+        if let Some(id) = &from_id {
+            params.push(("fromId", id.as_str()));
+        }
+
+        let mut headers = vec![];
+        if let Some(key) = &self.api_key {
+            headers.push((HEADER_API_KEY, key.as_str()));
+        }
+
+        self.limiter.acquire(WEIGHT_HISTORICAL_TRADES);
+        let resp = self
+            .rest
+            .get_with_params_and_headers(&url, &params, &headers)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<Vec<TradeData>>(json_str)?;
+
+        res.into_iter().map(Trade::from_trade_data).collect()
+    }
+
+    /// Feeds rate limiter with the response headers and checks response status.
+    fn handle_limits(&self, status: StatusCode, headers: &HeaderMap) -> Result<(), ClientError> {
+        let used_weight = headers
+            .get(HEADER_REQ_WEIGHT)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let retry_after = headers
+            .get(HEADER_RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
 
         debug!(
-            "got status: {} and req weight per minute: {} and retry after: {}",
-            resp.status(),
-            resp.headers()
-                .get(HEADER_REQ_WEIGHT)
-                .unwrap()
-                .to_str()
-                .unwrap(),
-            resp.headers()
-                .get(HEADER_RETRY_AFTER)
-                .unwrap_or(&HeaderValue::from_str("").unwrap())
-                .to_str()
-                .unwrap(),
+            "got status: {status} and req weight per minute: {used_weight:?} and retry after: {retry_after:?}",
         );
 
+        if let Some(used) = used_weight {
+            self.limiter.update_used(used);
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::IM_A_TEAPOT {
+            if let Some(secs) = retry_after {
+                self.limiter.ban(Duration::from_secs(secs));
+            }
+
+            return Err(ClientError::RateLimited(retry_after));
+        }
+
+        if !status.is_success() {
+            return Err(ClientError::Status(status.as_u16()));
+        }
+
+        Ok(())
+    }
+}
+
+impl MarketDataSource for Client {
+    fn kline(
+        &self,
+        symbol: String,
+        interval: Interval,
+        start_time: i64,
+        limit: usize,
+    ) -> Result<Vec<Kline>, ClientError> {
+        self.price_kline(symbol, interval, PriceKind::Last, start_time, limit)
+    }
+
+    fn price_kline(
+        &self,
+        symbol: String,
+        interval: Interval,
+        kind: PriceKind,
+        start_time: i64,
+        limit: usize,
+    ) -> Result<Vec<Kline>, ClientError> {
+        let (url, params) = self.price_kline_request(symbol, interval, kind, start_time, limit)?;
+        let params = params
+            .iter()
+            .map(|(k, v)| (*k, v.as_str()))
+            .collect::<Vec<_>>();
+
+        self.get_klines(&url, &params)
+    }
+
+    fn price_kline_async(
+        self: Arc<Self>,
+        symbol: String,
+        interval: Interval,
+        kind: PriceKind,
+        start_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<Kline>> {
+        Box::pin(async move {
+            let (url, params) =
+                self.price_kline_request(symbol, interval, kind, start_time, limit)?;
+            let res = self
+                .get_async::<Vec<KlineData>>(&url, &params, WEIGHT_KLINE)
+                .await?;
+
+            res.into_iter().map(Kline::from_kline_data).collect()
+        })
+    }
+
+    fn agg_trades(
+        &self,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        from_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AggTrade>, ClientError> {
+        let url = self.url(PATH_AGG_TRADES, PATH_FUTURES_AGG_TRADES);
+        let limit = limit.to_string();
+        let start_time = start_time.to_string();
+        let end_time = end_time.to_string();
+        let from_id = from_id.map(|id| id.to_string());
+
+        let mut params = vec![("symbol", symbol.as_str()), ("limit", limit.as_str())];
+        match &from_id {
+            Some(id) => params.push(("fromId", id.as_str())),
+            None => {
+                params.push(("startTime", start_time.as_str()));
+                params.push(("endTime", end_time.as_str()));
+            }
+        }
+
+        self.limiter.acquire(WEIGHT_AGG_TRADES);
+        let resp = self.rest.get_with_params(&url, &params)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
         let json_str = &resp.text()?;
-        let res = serde_json::from_str::<Vec<KlineData>>(json_str)?;
+        let res = serde_json::from_str::<Vec<AggTradeData>>(json_str)?;
 
-        Ok(res.into_iter().map(Kline::from_kline_data).collect())
+        res.into_iter().map(AggTrade::from_agg_trade_data).collect()
     }
 
-    pub fn info() -> Info {
-        let url = format!("{}{}", BASE_URL, PATH_INFO);
-        let resp = Rest::new().get(&url).unwrap();
-        debug!(
-            "got status: {} and req weight per minute: {} and retry after: {}",
-            resp.status(),
-            resp.headers()
-                .get(HEADER_REQ_WEIGHT)
-                .unwrap()
-                .to_str()
-                .unwrap(),
-            resp.headers()
-                .get(HEADER_RETRY_AFTER)
-                .unwrap_or(&HeaderValue::from_str("").unwrap())
-                .to_str()
-                .unwrap()
+    fn info(&self) -> Result<Info, ClientError> {
+        let url = self.url(PATH_INFO, PATH_FUTURES_INFO);
+        self.limiter.acquire(WEIGHT_INFO);
+        let resp = self.rest.get(&url)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let mut res = serde_json::from_str::<Info>(json_str)?;
+        res.symbols.iter_mut().for_each(|s| s.market = self.market);
+
+        Ok(res)
+    }
+
+    fn server_time(&self) -> Result<i64, ClientError> {
+        let url = self.url(PATH_TIME, PATH_FUTURES_TIME);
+        self.limiter.acquire(WEIGHT_TIME);
+        let resp = self.rest.get(&url)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<ServerTime>(json_str)?;
+
+        Ok(res.server_time)
+    }
+
+    fn depth(&self, symbol: String, limit: usize) -> Result<Depth, ClientError> {
+        let url = self.url(PATH_DEPTH, PATH_FUTURES_DEPTH);
+        let params = &[("symbol", symbol.as_str()), ("limit", &limit.to_string())];
+
+        self.limiter.acquire(depth_weight(limit));
+        let resp = self.rest.get_with_params(&url, params)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<DepthData>(json_str)?;
+
+        Depth::from_depth_data(res)
+    }
+
+    fn ticker_24h(&self) -> Result<Vec<Ticker24h>, ClientError> {
+        let url = self.url(PATH_TICKER_24H, PATH_FUTURES_TICKER_24H);
+
+        self.limiter.acquire(WEIGHT_TICKER_24H);
+        let resp = self.rest.get(&url)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<Vec<Ticker24hData>>(json_str)?;
+
+        res.into_iter().map(Ticker24h::from_ticker_data).collect()
+    }
+
+    fn funding_rate(
+        &self,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> Result<Vec<FundingRate>, ClientError> {
+        let (url, params) = self.funding_rate_request(symbol, start_time, end_time, limit)?;
+        let params = params
+            .iter()
+            .map(|(k, v)| (*k, v.as_str()))
+            .collect::<Vec<_>>();
+
+        self.limiter.acquire(WEIGHT_FUNDING_RATE);
+        let resp = self.rest.get_with_params(&url, &params)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<Vec<FundingRateData>>(json_str)?;
+
+        res.into_iter()
+            .map(FundingRate::from_funding_rate_data)
+            .collect()
+    }
+
+    fn funding_rate_async(
+        self: Arc<Self>,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<FundingRate>> {
+        Box::pin(async move {
+            let (url, params) = self.funding_rate_request(symbol, start_time, end_time, limit)?;
+            let res = self
+                .get_async::<Vec<FundingRateData>>(&url, &params, WEIGHT_FUNDING_RATE)
+                .await?;
+
+            res.into_iter()
+                .map(FundingRate::from_funding_rate_data)
+                .collect()
+        })
+    }
+
+    fn open_interest_hist(
+        &self,
+        symbol: String,
+        period: Interval,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> Result<Vec<OpenInterest>, ClientError> {
+        let (url, params) =
+            self.open_interest_hist_request(symbol, period, start_time, end_time, limit)?;
+        let params = params
+            .iter()
+            .map(|(k, v)| (*k, v.as_str()))
+            .collect::<Vec<_>>();
+
+        self.limiter.acquire(WEIGHT_OPEN_INTEREST_HIST);
+        let resp = self.rest.get_with_params(&url, &params)?;
+        self.handle_limits(resp.status(), resp.headers())?;
+
+        let json_str = &resp.text()?;
+        let res = serde_json::from_str::<Vec<OpenInterestData>>(json_str)?;
+
+        res.into_iter()
+            .map(OpenInterest::from_open_interest_data)
+            .collect()
+    }
+
+    fn open_interest_hist_async(
+        self: Arc<Self>,
+        symbol: String,
+        period: Interval,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<OpenInterest>> {
+        Box::pin(async move {
+            let (url, params) =
+                self.open_interest_hist_request(symbol, period, start_time, end_time, limit)?;
+            let res = self
+                .get_async::<Vec<OpenInterestData>>(&url, &params, WEIGHT_OPEN_INTEREST_HIST)
+                .await?;
+
+            res.into_iter()
+                .map(OpenInterest::from_open_interest_data)
+                .collect()
+        })
+    }
+}
+
+/// Depth request weight grows with the number of requested levels.
+fn depth_weight(limit: usize) -> usize {
+    match limit {
+        0..=100 => 5,
+        101..=500 => 25,
+        501..=1000 => 50,
+        _ => 250,
+    }
+}
+
+#[cfg(test)]
+mod client_tests {
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use crate::network::errors::RestError;
+    use crate::network::fixtures::{Fault, Replayer};
+    use crate::network::rest::Mode;
+    use crate::sources::binance::with_retry;
+
+    use super::*;
+
+    /// Starts local http server answering every request with the body.
+    fn serve(body: &'static str) -> String {
+        serve_with_status("200 OK", "", body)
+    }
+
+    /// Starts local http server answering every request with the status, extra headers and body.
+    fn serve_with_status(
+        status: &'static str,
+        headers: &'static str,
+        body: &'static str,
+    ) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).unwrap();
+
+                let resp = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\n{}{}: 1\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    headers,
+                    HEADER_REQ_WEIGHT,
+                    body.len(),
+                    body
+                );
+                stream.write_all(resp.as_bytes()).unwrap();
+            }
+        });
+
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn test_kline() {
+        let base_url = serve(
+            r#"[[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]]"#,
+        );
+        let client = Client::new(base_url);
+
+        let res = client.kline("BTCUSDT".to_string(), Interval::Day, 0, 1);
+        let klines = res.unwrap();
+
+        assert_eq!(klines.len(), 1);
+        assert_eq!(klines[0].t_open, 1499040000000);
+        assert_eq!(klines[0].t_close, 1499644799999);
+        assert_eq!(klines[0].number_of_trades, 308);
+        assert_eq!(klines[0].open, 0.0163479);
+    }
+
+    #[test]
+    fn test_price_kline_async() {
+        let base_url = serve(
+            r#"[[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]]"#,
+        );
+        let client = Arc::new(Client::new(base_url));
+
+        let res = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(client.price_kline_async(
+                "BTCUSDT".to_string(),
+                Interval::Day,
+                PriceKind::Last,
+                0,
+                1,
+            ));
+        let klines = res.unwrap();
+
+        assert_eq!(klines.len(), 1);
+        assert_eq!(klines[0].t_open, 1499040000000);
+        assert_eq!(klines[0].open, 0.0163479);
+    }
+
+    #[test]
+    fn test_kline_malformed_number() {
+        let base_url = serve(
+            r#"[[1499040000000,"0.01634790","abc","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]]"#,
+        );
+        let client = Client::new(base_url);
+
+        let res = client.kline("BTCUSDT".to_string(), Interval::Day, 0, 1);
+
+        assert!(matches!(res, Err(ClientError::Parse(value)) if value == "abc"));
+    }
+
+    #[test]
+    fn test_info_failed() {
+        let base_url = serve_with_status("500 Internal Server Error", "", "");
+        let client = Client::new(base_url);
+
+        assert!(matches!(client.info(), Err(ClientError::Status(500))));
+    }
+
+    #[test]
+    fn test_info_filters() {
+        let base_url = serve(
+            r#"{"symbols":[{"symbol":"ETHBTC","status":"TRADING","baseAsset":"ETH","baseAssetPrecision":8,"quoteAsset":"BTC","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"allowTrailingStop":false,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true,"filters":[{"filterType":"PRICE_FILTER","minPrice":"0.00001000","maxPrice":"922327.00000000","tickSize":"0.00001000"},{"filterType":"LOT_SIZE","minQty":"0.00010000","maxQty":"100000.00000000","stepSize":"0.00010000"}]}]}"#,
+        );
+        let client = Client::new(base_url);
+
+        let info = client.info().unwrap();
+        let symbol = &info.symbols[0];
+
+        assert_eq!(symbol.quote_asset(), "BTC");
+        assert_eq!(symbol.order_types(), &[OrderType::Limit, OrderType::Market]);
+        assert_eq!(symbol.filters().len(), 2);
+        assert_eq!(symbol.round_price(0.0712345), 0.07123);
+        assert_eq!(symbol.round_qty(1.23456), 1.2345);
+    }
+
+    #[test]
+    fn test_server_time() {
+        let base_url = serve(r#"{"serverTime":1499827319559}"#);
+        let client = Client::new(base_url);
+
+        assert_eq!(client.server_time().unwrap(), 1499827319559);
+    }
+
+    #[test]
+    fn test_kline_rate_limited() {
+        let base_url = serve_with_status("429 Too Many Requests", "Retry-After: 0\r\n", "{}");
+        let client = Client::new(base_url);
+
+        let res = client.kline("BTCUSDT".to_string(), Interval::Day, 0, 1);
+
+        assert!(matches!(res, Err(ClientError::RateLimited(Some(0)))));
+    }
+
+    #[test]
+    fn test_agg_trades() {
+        let base_url = serve(
+            r#"[{"a":26129,"p":"0.01633102","q":"4.70443515","f":27781,"l":27781,"T":1498793709153,"m":true,"M":true}]"#,
+        );
+        let client = Client::new(base_url);
+
+        let res = client.agg_trades("BTCUSDT".to_string(), 0, 1, None, 1);
+        let trades = res.unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].id, 26129);
+        assert_eq!(trades[0].time, 1498793709153);
+        assert!(!trades[0].taker_buy());
+    }
+
+    #[test]
+    fn test_depth() {
+        let base_url = serve(
+            r#"{"lastUpdateId":1027024,"bids":[["4.00000000","431.00000000"],["3.90000000","10.00000000"]],"asks":[["4.00000200","12.00000000"]]}"#,
+        );
+        let client = Client::new(base_url);
+
+        let depth = client.depth("BTCUSDT".to_string(), 5).unwrap();
+
+        assert_eq!(depth.last_update_id, 1027024);
+        assert_eq!(depth.bids.len(), 2);
+        assert_eq!(depth.bids[1].price, 3.9);
+        assert_eq!(depth.asks[0].qty, 12.0);
+    }
+
+    #[test]
+    fn test_ticker_24h() {
+        let base_url = serve(
+            r#"[{"symbol":"BNBBTC","priceChange":"-94.99999800","priceChangePercent":"-95.960","weightedAvgPrice":"0.29628482","prevClosePrice":"0.10002000","lastPrice":"4.00000200","lastQty":"200.00000000","bidPrice":"4.00000000","bidQty":"100.00000000","askPrice":"4.00000200","askQty":"100.00000000","openPrice":"99.00000000","highPrice":"100.00000000","lowPrice":"0.10000000","volume":"8913.30000000","quoteVolume":"15.30000000","openTime":1499783499040,"closeTime":1499869899040,"firstId":28385,"lastId":28460,"count":76}]"#,
+        );
+        let client = Client::new(base_url);
+
+        let tickers = client.ticker_24h().unwrap();
+
+        assert_eq!(tickers.len(), 1);
+        assert_eq!(tickers[0].symbol, "BNBBTC");
+        assert_eq!(tickers[0].price_change_percent, -95.96);
+        assert_eq!(tickers[0].quote_volume, 15.3);
+        assert_eq!(tickers[0].count, 76);
+    }
+
+    #[test]
+    fn test_futures_info() {
+        let base_url = serve(
+            r#"{"symbols":[{"symbol":"BTCUSDT","pair":"BTCUSDT","contractType":"PERPETUAL","status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","marginAsset":"USDT","pricePrecision":2,"quantityPrecision":3,"baseAssetPrecision":8,"quotePrecision":8,"filters":[{"filterType":"MIN_NOTIONAL","notional":"5.0"}],"orderTypes":["LIMIT","STOP_MARKET"]}]}"#,
+        );
+        let client = Client::new(base_url).with_market(Market::UsdFutures);
+
+        let info = client.info().unwrap();
+        let symbol = &info.symbols[0];
+
+        assert_eq!(symbol.market(), Market::UsdFutures);
+        assert_eq!(symbol.quote_asset_precision(), 2);
+        assert!(symbol.active());
+    }
+
+    #[test]
+    fn test_mark_price_kline() {
+        let base_url = serve(
+            r#"[[1591256400000,"9653.69440000","9653.69640000","9651.38600000","9651.55200000","0 ",1591256459999,"0",60,"0","0","0"]]"#,
+        );
+        let client = Client::new(base_url).with_market(Market::UsdFutures);
+
+        let klines = client
+            .price_kline(
+                "BTCUSDT".to_string(),
+                Interval::Minute,
+                PriceKind::Mark,
+                0,
+                1,
+            )
+            .unwrap();
+
+        assert_eq!(klines[0].close, 9651.552);
+        assert_eq!(klines[0].volume, 0.0);
+
+        let spot = Client::new(client.base_url().to_string());
+        let res = spot.price_kline(
+            "BTCUSDT".to_string(),
+            Interval::Minute,
+            PriceKind::Mark,
+            0,
+            1,
+        );
+        assert!(matches!(res, Err(ClientError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_funding_rate() {
+        let base_url = serve(
+            r#"[{"symbol":"BTCUSDT","fundingRate":"-0.03750000","fundingTime":1570608000000,"markPrice":"34287.54619963"},{"symbol":"BTCUSDT","fundingRate":"0.00010000","fundingTime":1570636800000,"markPrice":""}]"#,
         );
-        let json_str = &resp.text().unwrap();
-        serde_json::from_str::<Info>(json_str).unwrap()
+        let client = Client::new(base_url).with_market(Market::UsdFutures);
+
+        let rates = client
+            .funding_rate("BTCUSDT".to_string(), 0, 1, 1000)
+            .unwrap();
+
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].funding_time, 1570608000000);
+        assert_eq!(rates[0].funding_rate, -0.0375);
+        assert_eq!(rates[0].mark_price, Some(34287.54619963));
+        assert_eq!(rates[1].mark_price, None);
+    }
+
+    #[test]
+    fn test_historical_trades() {
+        let base_url = serve(
+            r#"[{"id":28457,"price":"4.00000100","qty":"12.00000000","quoteQty":"48.000012","time":1499865549590,"isBuyerMaker":true,"isBestMatch":true}]"#,
+        );
+        let client = Client::new(base_url).with_api_key("key".to_string());
+
+        let res = client
+            .historical_trades("BNBBTC".to_string(), Some(28457), 1)
+            .unwrap();
+        assert_eq!(res[0].id, 28457);
+        assert_eq!(res[0].quote_qty, 48.000012);
+        assert!(res[0].buyer_maker);
+    }
+
+    #[test]
+    fn test_open_interest_hist() {
+        let base_url = serve(
+            r#"[{"symbol":"BTCUSDT","sumOpenInterest":"20403.63700000","sumOpenInterestValue":"150570784.07809979","timestamp":1583127900000}]"#,
+        );
+        let client = Client::new(base_url).with_market(Market::UsdFutures);
+
+        let res = client
+            .open_interest_hist("BTCUSDT".to_string(), Interval::Minutes5, 0, 1, 30)
+            .unwrap();
+        assert_eq!(res[0].time, 1583127900000);
+        assert_eq!(res[0].open_interest, 20403.637);
+
+        let res = client.open_interest_hist("BTCUSDT".to_string(), Interval::Minute, 0, 1, 30);
+        assert!(matches!(res, Err(ClientError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_kline_record_replay() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let base_url = serve(
+            r#"[[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]]"#,
+        );
+
+        let client = Client::new(base_url.clone())
+            .with_rest(Rest::new().with_mode(Mode::Record(dir.clone())));
+        let recorded = client
+            .kline("BTCUSDT".to_string(), Interval::Day, 0, 1)
+            .unwrap();
+
+        let replayer = Replayer::new(dir.clone())
+            .with_fault(PATH_KLINE, Fault::Timeout, 1)
+            .with_fault(PATH_KLINE, Fault::TooManyRequests(Some(0)), 1)
+            .with_fault(PATH_KLINE, Fault::MalformedJson, 1)
+            .with_fault(PATH_KLINE, Fault::Status(502), 2);
+        let client = Client::new(base_url)
+            .with_rest(Rest::new().with_mode(Mode::Replay(Arc::new(replayer))));
+        let kline = || client.kline("BTCUSDT".to_string(), Interval::Day, 0, 1);
+
+        assert!(matches!(
+            kline(),
+            Err(ClientError::Rest(RestError::Timeout(_)))
+        ));
+        assert!(matches!(kline(), Err(ClientError::RateLimited(Some(0)))));
+        assert!(matches!(kline(), Err(ClientError::Serialization(_))));
+        assert_eq!(with_retry(3, Duration::ZERO, kline).unwrap(), recorded);
+
+        fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use super::{client::parse_number, errors::ClientError};
+
+/// Limits of order book levels accepted by binance depth endpoint.
+pub const DEPTH_LIMITS: [usize; 8] = [5, 10, 20, 50, 100, 500, 1000, 5000];
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct DepthLevel {
+    pub price: f64,
+    pub qty: f64,
+}
+
+/// Order book snapshot.
+///
+/// Bids are ordered from the best (highest) price, asks from the best (lowest) price.
+#[derive(Debug, Clone, Default)]
+pub struct Depth {
+    pub last_update_id: i64,
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct DepthData {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: i64,
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+impl Depth {
+    pub(super) fn from_depth_data(data: DepthData) -> Result<Self, ClientError> {
+        Ok(Depth {
+            last_update_id: data.last_update_id,
+            bids: data
+                .bids
+                .iter()
+                .map(DepthLevel::from_pair)
+                .collect::<Result<_, _>>()?,
+            asks: data
+                .asks
+                .iter()
+                .map(DepthLevel::from_pair)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl DepthLevel {
+    fn from_pair(pair: &[String; 2]) -> Result<Self, ClientError> {
+        Ok(DepthLevel {
+            price: parse_number(&pair[0])?,
+            qty: parse_number(&pair[1])?,
+        })
+    }
+}
@@ -1,5 +1,7 @@
 use quick_error::quick_error;
 
+use crate::network::errors::RestError;
+
 quick_error! {
     #[derive(Debug)]
     pub enum ClientError {
@@ -7,9 +9,44 @@ quick_error! {
             from()
             display("{}", err)
         }
+        Rest(err: RestError) {
+            from()
+            display("{}", err)
+        }
         Serialization(err: serde_json::Error) {
             from()
             display("{}", err)
         }
+        RateLimited(retry_after: Option<u64>) {
+            display("rate limit exceeded; retry after: {retry_after:?}")
+        }
+        Status(code: u16) {
+            display("unexpected response status: {code}")
+        }
+        Parse(value: String) {
+            display("failed to parse number: {value:?}")
+        }
+        Task(err: tokio::task::JoinError) {
+            from()
+            display("request task failed: {}", err)
+        }
+        Unsupported(what: String) {
+            display("not supported by the market: {what}")
+        }
+    }
+}
+
+impl ClientError {
+    /// Returns true if the same request may succeed later.
+    pub fn retryable(&self) -> bool {
+        match self {
+            ClientError::Reqwest(_) | ClientError::RateLimited(_) => true,
+            ClientError::Rest(err) => err.retryable(),
+            ClientError::Status(code) => *code >= 500,
+            ClientError::Serialization(_)
+            | ClientError::Parse(_)
+            | ClientError::Task(_)
+            | ClientError::Unsupported(_) => false,
+        }
     }
 }
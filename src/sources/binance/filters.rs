@@ -0,0 +1,444 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::warn;
+
+/// Trading rule of a symbol from exchange info.
+///
+/// Decimal values are sent by binance as strings and parsed to f64.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "filterType")]
+pub enum Filter {
+    #[serde(rename = "PRICE_FILTER")]
+    Price(PriceFilter),
+    #[serde(rename = "PERCENT_PRICE")]
+    PercentPrice(PercentPrice),
+    #[serde(rename = "PERCENT_PRICE_BY_SIDE")]
+    PercentPriceBySide(PercentPriceBySide),
+    #[serde(rename = "LOT_SIZE")]
+    LotSize(LotSize),
+    #[serde(rename = "MARKET_LOT_SIZE")]
+    MarketLotSize(LotSize),
+    #[serde(rename = "MIN_NOTIONAL")]
+    MinNotional(MinNotional),
+    #[serde(rename = "NOTIONAL")]
+    Notional(Notional),
+    #[serde(rename = "ICEBERG_PARTS")]
+    IcebergParts(IcebergParts),
+    #[serde(rename = "MAX_NUM_ORDERS")]
+    MaxNumOrders(MaxNumOrders),
+    #[serde(rename = "MAX_NUM_ALGO_ORDERS")]
+    MaxNumAlgoOrders(MaxNumAlgoOrders),
+    #[serde(rename = "MAX_POSITION")]
+    MaxPosition(MaxPosition),
+    #[serde(rename = "TRAILING_DELTA")]
+    TrailingDelta(TrailingDelta),
+    /// Filter type which is not supported yet.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFilter {
+    #[serde(with = "decimal")]
+    pub min_price: f64,
+    #[serde(with = "decimal")]
+    pub max_price: f64,
+    #[serde(with = "decimal")]
+    pub tick_size: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PercentPrice {
+    #[serde(with = "decimal")]
+    pub multiplier_up: f64,
+    #[serde(with = "decimal")]
+    pub multiplier_down: f64,
+    /// Not set on futures markets.
+    #[serde(default)]
+    pub avg_price_mins: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PercentPriceBySide {
+    #[serde(with = "decimal")]
+    pub bid_multiplier_up: f64,
+    #[serde(with = "decimal")]
+    pub bid_multiplier_down: f64,
+    #[serde(with = "decimal")]
+    pub ask_multiplier_up: f64,
+    #[serde(with = "decimal")]
+    pub ask_multiplier_down: f64,
+    pub avg_price_mins: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotSize {
+    #[serde(with = "decimal")]
+    pub min_qty: f64,
+    #[serde(with = "decimal")]
+    pub max_qty: f64,
+    #[serde(with = "decimal")]
+    pub step_size: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinNotional {
+    /// Futures markets name it notional.
+    #[serde(with = "decimal", alias = "notional")]
+    pub min_notional: f64,
+    #[serde(default)]
+    pub apply_to_market: bool,
+    #[serde(default)]
+    pub avg_price_mins: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notional {
+    #[serde(with = "decimal")]
+    pub min_notional: f64,
+    pub apply_min_to_market: bool,
+    #[serde(with = "decimal")]
+    pub max_notional: f64,
+    pub apply_max_to_market: bool,
+    pub avg_price_mins: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IcebergParts {
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxNumOrders {
+    #[serde(alias = "limit")]
+    pub max_num_orders: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxNumAlgoOrders {
+    #[serde(alias = "limit")]
+    pub max_num_algo_orders: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxPosition {
+    #[serde(with = "decimal")]
+    pub max_position: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrailingDelta {
+    pub min_trailing_above_delta: i64,
+    pub max_trailing_above_delta: i64,
+    pub min_trailing_below_delta: i64,
+    pub max_trailing_below_delta: i64,
+}
+
+/// Order type allowed for a symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum OrderType {
+    Limit,
+    LimitMaker,
+    Market,
+    StopLoss,
+    StopLossLimit,
+    TakeProfit,
+    TakeProfitLimit,
+    Other(String),
+}
+
+impl From<String> for OrderType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "LIMIT" => OrderType::Limit,
+            "LIMIT_MAKER" => OrderType::LimitMaker,
+            "MARKET" => OrderType::Market,
+            "STOP_LOSS" => OrderType::StopLoss,
+            "STOP_LOSS_LIMIT" => OrderType::StopLossLimit,
+            "TAKE_PROFIT" => OrderType::TakeProfit,
+            "TAKE_PROFIT_LIMIT" => OrderType::TakeProfitLimit,
+            _ => OrderType::Other(s),
+        }
+    }
+}
+
+impl From<OrderType> for String {
+    fn from(t: OrderType) -> Self {
+        t.to_string()
+    }
+}
+
+impl Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            OrderType::Limit => "LIMIT",
+            OrderType::LimitMaker => "LIMIT_MAKER",
+            OrderType::Market => "MARKET",
+            OrderType::StopLoss => "STOP_LOSS",
+            OrderType::StopLossLimit => "STOP_LOSS_LIMIT",
+            OrderType::TakeProfit => "TAKE_PROFIT",
+            OrderType::TakeProfitLimit => "TAKE_PROFIT_LIMIT",
+            OrderType::Other(s) => s,
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Price(p) => write!(
+                f,
+                "price: min {}, max {}, tick {}",
+                p.min_price, p.max_price, p.tick_size
+            ),
+            Filter::PercentPrice(p) => write!(
+                f,
+                "percent price: up x{}, down x{}, avg {} mins",
+                p.multiplier_up, p.multiplier_down, p.avg_price_mins
+            ),
+            Filter::PercentPriceBySide(p) => write!(
+                f,
+                "percent price by side: bid up x{}, bid down x{}, ask up x{}, ask down x{}, avg {} mins",
+                p.bid_multiplier_up,
+                p.bid_multiplier_down,
+                p.ask_multiplier_up,
+                p.ask_multiplier_down,
+                p.avg_price_mins
+            ),
+            Filter::LotSize(l) => write!(
+                f,
+                "lot size: min {}, max {}, step {}",
+                l.min_qty, l.max_qty, l.step_size
+            ),
+            Filter::MarketLotSize(l) => write!(
+                f,
+                "market lot size: min {}, max {}, step {}",
+                l.min_qty, l.max_qty, l.step_size
+            ),
+            Filter::MinNotional(n) => write!(
+                f,
+                "min notional: {}, market: {}",
+                n.min_notional, n.apply_to_market
+            ),
+            Filter::Notional(n) => write!(
+                f,
+                "notional: min {}, max {}",
+                n.min_notional, n.max_notional
+            ),
+            Filter::IcebergParts(p) => write!(f, "iceberg parts: {}", p.limit),
+            Filter::MaxNumOrders(m) => write!(f, "max orders: {}", m.max_num_orders),
+            Filter::MaxNumAlgoOrders(m) => {
+                write!(f, "max algo orders: {}", m.max_num_algo_orders)
+            }
+            Filter::MaxPosition(m) => write!(f, "max position: {}", m.max_position),
+            Filter::TrailingDelta(d) => write!(
+                f,
+                "trailing delta: above {}-{}, below {}-{}",
+                d.min_trailing_above_delta,
+                d.max_trailing_above_delta,
+                d.min_trailing_below_delta,
+                d.max_trailing_below_delta
+            ),
+            Filter::Unknown => write!(f, "unknown filter"),
+        }
+    }
+}
+
+impl PriceFilter {
+    /// Rounds price to the nearest tick.
+    pub fn round(&self, price: f64) -> f64 {
+        round_to_step(price, self.tick_size, false)
+    }
+
+    /// Checks that price is inside filter range. Zero limit means the limit is disabled.
+    pub fn allows(&self, price: f64) -> bool {
+        (self.min_price == 0.0 || price >= self.min_price)
+            && (self.max_price == 0.0 || price <= self.max_price)
+    }
+}
+
+impl LotSize {
+    /// Rounds quantity down to the step, so it never exceeds requested quantity.
+    pub fn round(&self, qty: f64) -> f64 {
+        round_to_step(qty, self.step_size, true)
+    }
+
+    pub fn allows(&self, qty: f64) -> bool {
+        qty >= self.min_qty && (self.max_qty == 0.0 || qty <= self.max_qty)
+    }
+}
+
+/// Rounds value to the multiple of step removing float noise left after division.
+fn round_to_step(v: f64, step: f64, floor: bool) -> f64 {
+    if step <= 0.0 {
+        return v;
+    }
+
+    // small epsilon keeps values which are already multiples of step unchanged
+    let steps = match floor {
+        true => (v / step + 1e-9).floor(),
+        false => (v / step).round(),
+    };
+
+    let decimals = (-step.log10()).ceil().max(0.0) as i32;
+    let scale = 10f64.powi(decimals);
+
+    (steps * step * scale).round() / scale
+}
+
+/// Deserializes filters falling back to [`Filter::Unknown`] for the ones which fail to parse,
+/// so a single changed filter does not fail the whole exchange info.
+pub fn deserialize_lenient<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Filter>, D::Error> {
+    let values = Vec::<serde_json::Value>::deserialize(d)?;
+
+    Ok(values
+        .into_iter()
+        .map(|v| match serde_json::from_value::<Filter>(v.clone()) {
+            Ok(filter) => filter,
+            Err(err) => {
+                warn!("failed to parse filter {v}: {err}");
+                Filter::Unknown
+            }
+        })
+        .collect())
+}
+
+/// Serializes f64 as decimal string the same way binance sends it.
+mod decimal {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&v.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse::<f64>().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod filters_tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let filters = serde_json::from_str::<Vec<Filter>>(
+            r#"[
+                {"filterType":"PRICE_FILTER","minPrice":"0.00000100","maxPrice":"922327.00000000","tickSize":"0.00000100"},
+                {"filterType":"LOT_SIZE","minQty":"0.00010000","maxQty":"100000.00000000","stepSize":"0.00010000"},
+                {"filterType":"MIN_NOTIONAL","minNotional":"0.00010000","applyToMarket":true,"avgPriceMins":5},
+                {"filterType":"SOMETHING_NEW","value":"1"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            filters[0],
+            Filter::Price(PriceFilter {
+                min_price: 0.000001,
+                max_price: 922327.0,
+                tick_size: 0.000001,
+            })
+        );
+        assert!(matches!(filters[1], Filter::LotSize(l) if l.step_size == 0.0001));
+        assert!(matches!(filters[2], Filter::MinNotional(n) if n.apply_to_market));
+        assert_eq!(filters[3], Filter::Unknown);
+
+        let json = serde_json::to_string(&filters[..3]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Filter>>(&json).unwrap(),
+            filters[..3]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_futures() {
+        let filters = serde_json::from_str::<Vec<Filter>>(
+            r#"[
+                {"filterType":"MIN_NOTIONAL","notional":"5.0"},
+                {"filterType":"MAX_NUM_ORDERS","limit":200},
+                {"filterType":"PERCENT_PRICE","multiplierUp":"1.0500","multiplierDown":"0.9500","multiplierDecimal":"4"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(matches!(filters[0], Filter::MinNotional(n) if n.min_notional == 5.0));
+        assert!(matches!(filters[1], Filter::MaxNumOrders(n) if n.max_num_orders == 200));
+        assert!(matches!(filters[2], Filter::PercentPrice(p) if p.multiplier_up == 1.05));
+    }
+
+    #[test]
+    fn test_deserialize_lenient() {
+        #[derive(Deserialize)]
+        struct Symbol {
+            #[serde(deserialize_with = "deserialize_lenient")]
+            filters: Vec<Filter>,
+        }
+
+        let symbol = serde_json::from_str::<Symbol>(
+            r#"{"filters":[
+                {"filterType":"PRICE_FILTER","minPrice":"0.01","maxPrice":"1000","tickSize":"0.01"},
+                {"filterType":"LOT_SIZE","minQty":0.001,"maxQty":"100","stepSize":"0.001"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(symbol.filters[0], Filter::Price(p) if p.tick_size == 0.01));
+        assert_eq!(symbol.filters[1], Filter::Unknown);
+    }
+
+    #[test]
+    fn test_order_type() {
+        let types =
+            serde_json::from_str::<Vec<OrderType>>(r#"["LIMIT","MARKET","NEW_TYPE"]"#).unwrap();
+
+        assert_eq!(
+            types,
+            vec![
+                OrderType::Limit,
+                OrderType::Market,
+                OrderType::Other("NEW_TYPE".to_string())
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&types).unwrap(),
+            r#"["LIMIT","MARKET","NEW_TYPE"]"#
+        );
+    }
+
+    #[test]
+    fn test_round() {
+        let price = PriceFilter {
+            min_price: 0.01,
+            max_price: 1000.0,
+            tick_size: 0.01,
+        };
+        assert_eq!(price.round(10.126), 10.13);
+        assert_eq!(price.round(0.3), 0.3);
+        assert!(!price.allows(1000.01));
+
+        let lot = LotSize {
+            min_qty: 0.001,
+            max_qty: 100.0,
+            step_size: 0.001,
+        };
+        assert_eq!(lot.round(1.2349), 1.234);
+        assert_eq!(lot.round(0.3), 0.3);
+        assert!(!lot.allows(0.0001));
+    }
+}
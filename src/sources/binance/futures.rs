@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use super::{client::parse_number, errors::ClientError, Interval};
+
+/// Funding events happen every 8 hours on most perpetual contracts.
+pub const FUNDING_PERIOD_MILLIS: i64 = 8 * 60 * 60 * 1000;
+
+/// Binance keeps open interest statistics only for the last 30 days.
+pub const OPEN_INTEREST_HISTORY_MILLIS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+/// Periods open interest statistics are aggregated by.
+pub const OPEN_INTEREST_PERIODS: [Interval; 9] = [
+    Interval::Minutes5,
+    Interval::Minutes15,
+    Interval::Minutes30,
+    Interval::Hour,
+    Interval::Hours2,
+    Interval::Hours4,
+    Interval::Hours6,
+    Interval::Hours12,
+    Interval::Day,
+];
+
+/// Funding paid between long and short positions of a perpetual contract.
+///
+/// Positive rate means longs pay shorts.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub funding_time: i64,
+    pub funding_rate: f64,
+    /// Mark price at the funding time. Binance does not return it for old events.
+    pub mark_price: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FundingRateData {
+    funding_time: i64,
+    funding_rate: String,
+    #[serde(default)]
+    mark_price: String,
+}
+
+impl FundingRate {
+    pub(super) fn from_funding_rate_data(data: FundingRateData) -> Result<Self, ClientError> {
+        Ok(FundingRate {
+            funding_time: data.funding_time,
+            funding_rate: parse_number(&data.funding_rate)?,
+            mark_price: match data.mark_price.is_empty() {
+                true => None,
+                false => Some(parse_number(&data.mark_price)?),
+            },
+        })
+    }
+}
+
+/// Total open positions of a futures contract at the end of a period.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct OpenInterest {
+    pub time: i64,
+    /// Open interest in contracts.
+    pub open_interest: f64,
+    /// Open interest in quote asset.
+    pub open_interest_value: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct OpenInterestData {
+    timestamp: i64,
+    sum_open_interest: String,
+    sum_open_interest_value: String,
+}
+
+impl OpenInterest {
+    pub(super) fn from_open_interest_data(data: OpenInterestData) -> Result<Self, ClientError> {
+        Ok(OpenInterest {
+            time: data.timestamp,
+            open_interest: parse_number(&data.sum_open_interest)?,
+            open_interest_value: parse_number(&data.sum_open_interest_value)?,
+        })
+    }
+}
@@ -1,16 +1,145 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+const MILLIS_SECOND: i64 = 1000;
+const MILLIS_MINUTE: i64 = 60 * MILLIS_SECOND;
+const MILLIS_HOUR: i64 = 60 * MILLIS_MINUTE;
+const MILLIS_DAY: i64 = 24 * MILLIS_HOUR;
+
+/// Kline intervals supported by binance.
 #[derive(Debug, Clone, Copy)]
 pub enum Interval {
+    Second,
     Minute,
+    Minutes3,
+    Minutes5,
+    Minutes15,
+    Minutes30,
     Hour,
+    Hours2,
+    Hours4,
+    Hours6,
+    Hours8,
+    Hours12,
     Day,
+    Days3,
+    Week,
+    Month,
 }
 
 impl Interval {
+    /// All intervals ordered from the finest to the coarsest.
+    pub const ALL: [Interval; 16] = [
+        Interval::Second,
+        Interval::Minute,
+        Interval::Minutes3,
+        Interval::Minutes5,
+        Interval::Minutes15,
+        Interval::Minutes30,
+        Interval::Hour,
+        Interval::Hours2,
+        Interval::Hours4,
+        Interval::Hours6,
+        Interval::Hours8,
+        Interval::Hours12,
+        Interval::Day,
+        Interval::Days3,
+        Interval::Week,
+        Interval::Month,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
+            Interval::Second => "1s",
             Interval::Minute => "1m",
+            Interval::Minutes3 => "3m",
+            Interval::Minutes5 => "5m",
+            Interval::Minutes15 => "15m",
+            Interval::Minutes30 => "30m",
             Interval::Hour => "1h",
+            Interval::Hours2 => "2h",
+            Interval::Hours4 => "4h",
+            Interval::Hours6 => "6h",
+            Interval::Hours8 => "8h",
+            Interval::Hours12 => "12h",
             Interval::Day => "1d",
+            Interval::Days3 => "3d",
+            Interval::Week => "1w",
+            Interval::Month => "1M",
+        }
+    }
+
+    /// Parses binance interval notation, e.g. `15m` or `1M`.
+    pub fn parse(s: &str) -> Option<Interval> {
+        Interval::ALL.iter().find(|i| i.as_str() == s).copied()
+    }
+
+    /// Duration of a single kline in millis.
+    ///
+    /// Returns None for intervals which do not have fixed length, e.g. month.
+    pub fn millis(&self) -> Option<i64> {
+        match self {
+            Interval::Second => Some(MILLIS_SECOND),
+            Interval::Minute => Some(MILLIS_MINUTE),
+            Interval::Minutes3 => Some(3 * MILLIS_MINUTE),
+            Interval::Minutes5 => Some(5 * MILLIS_MINUTE),
+            Interval::Minutes15 => Some(15 * MILLIS_MINUTE),
+            Interval::Minutes30 => Some(30 * MILLIS_MINUTE),
+            Interval::Hour => Some(MILLIS_HOUR),
+            Interval::Hours2 => Some(2 * MILLIS_HOUR),
+            Interval::Hours4 => Some(4 * MILLIS_HOUR),
+            Interval::Hours6 => Some(6 * MILLIS_HOUR),
+            Interval::Hours8 => Some(8 * MILLIS_HOUR),
+            Interval::Hours12 => Some(12 * MILLIS_HOUR),
+            Interval::Day => Some(MILLIS_DAY),
+            Interval::Days3 => Some(3 * MILLIS_DAY),
+            Interval::Week => Some(7 * MILLIS_DAY),
+            Interval::Month => None,
+        }
+    }
+
+    /// Returns open time of the kline of this interval containing ts.
+    ///
+    /// Klines up to 3 days are aligned to unix epoch, weeks start on monday
+    /// and months on the first day of month.
+    pub fn open_time(&self, ts: i64) -> i64 {
+        match self {
+            Interval::Week => {
+                // unix epoch is thursday so the first monday is 4 days later
+                let offset = 4 * MILLIS_DAY;
+                (ts - offset).div_euclid(7 * MILLIS_DAY) * 7 * MILLIS_DAY + offset
+            }
+            Interval::Month => {
+                let dt = NaiveDateTime::from_timestamp(ts.div_euclid(1000), 0);
+                NaiveDate::from_ymd(dt.year(), dt.month(), 1)
+                    .and_hms(0, 0, 0)
+                    .timestamp_millis()
+            }
+            _ => {
+                let millis = self.millis().unwrap();
+                ts.div_euclid(millis) * millis
+            }
+        }
+    }
+
+    /// Human readable name used in ui.
+    pub fn name(&self) -> &str {
+        match self {
+            Interval::Second => "1 second",
+            Interval::Minute => "1 minute",
+            Interval::Minutes3 => "3 minutes",
+            Interval::Minutes5 => "5 minutes",
+            Interval::Minutes15 => "15 minutes",
+            Interval::Minutes30 => "30 minutes",
+            Interval::Hour => "1 hour",
+            Interval::Hours2 => "2 hours",
+            Interval::Hours4 => "4 hours",
+            Interval::Hours6 => "6 hours",
+            Interval::Hours8 => "8 hours",
+            Interval::Hours12 => "12 hours",
+            Interval::Day => "1 day",
+            Interval::Days3 => "3 days",
+            Interval::Week => "1 week",
+            Interval::Month => "1 month",
         }
     }
 }
@@ -20,3 +149,88 @@ impl PartialEq for Interval {
         format!("{:?}", self) == format!("{:?}", other)
     }
 }
+
+/// Adds months to the timestamp in millis keeping the day of month if possible.
+///
+/// If the target month is shorter, the day is clamped to its last day.
+pub fn add_months(ts: i64, months: i64) -> i64 {
+    let dt = NaiveDateTime::from_timestamp(ts.div_euclid(1000), 0);
+    let millis = ts.rem_euclid(1000);
+
+    let total = dt.year() as i64 * 12 + dt.month0() as i64 + months;
+    let year = total.div_euclid(12) as i32;
+    let month = total.rem_euclid(12) as u32 + 1;
+
+    let mut day = dt.day();
+    while NaiveDate::from_ymd_opt(year, month, day).is_none() {
+        day -= 1;
+    }
+
+    NaiveDate::from_ymd(year, month, day)
+        .and_time(dt.time())
+        .timestamp_millis()
+        + millis
+}
+
+#[cfg(test)]
+mod interval_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        Interval::ALL.iter().for_each(|i| {
+            assert_eq!(Interval::parse(i.as_str()), Some(*i));
+        });
+
+        assert_eq!(Interval::parse("1M"), Some(Interval::Month));
+        assert_eq!(Interval::parse("1m"), Some(Interval::Minute));
+        assert_eq!(Interval::parse("2m"), None);
+    }
+
+    #[test]
+    fn test_open_time() {
+        let ts = NaiveDate::from_ymd(2022, 3, 17)
+            .and_hms(10, 42, 13)
+            .timestamp_millis();
+
+        assert_eq!(
+            Interval::Hour.open_time(ts),
+            NaiveDate::from_ymd(2022, 3, 17)
+                .and_hms(10, 0, 0)
+                .timestamp_millis()
+        );
+        assert_eq!(
+            Interval::Week.open_time(ts),
+            NaiveDate::from_ymd(2022, 3, 14)
+                .and_hms(0, 0, 0)
+                .timestamp_millis()
+        );
+        assert_eq!(
+            Interval::Month.open_time(ts),
+            NaiveDate::from_ymd(2022, 3, 1)
+                .and_hms(0, 0, 0)
+                .timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn test_add_months() {
+        let jan_31 = NaiveDate::from_ymd(2022, 1, 31)
+            .and_hms(10, 0, 0)
+            .timestamp_millis();
+        let feb_28 = NaiveDate::from_ymd(2022, 2, 28)
+            .and_hms(10, 0, 0)
+            .timestamp_millis();
+        let jan_31_next = NaiveDate::from_ymd(2023, 1, 31)
+            .and_hms(10, 0, 0)
+            .timestamp_millis();
+        let dec_31_prev = NaiveDate::from_ymd(2021, 12, 31)
+            .and_hms(10, 0, 0)
+            .timestamp_millis();
+
+        assert_eq!(add_months(jan_31, 1), feb_28);
+        assert_eq!(add_months(jan_31, 12), jan_31_next);
+        assert_eq!(add_months(jan_31, -1), dec_31_prev);
+        assert_eq!(add_months(jan_31 + 5, 0), jan_31 + 5);
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Binance market symbols are traded on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Market {
+    #[default]
+    Spot,
+    /// USDⓈ-M futures settled in stablecoins.
+    UsdFutures,
+}
+
+impl Market {
+    pub const ALL: [Market; 2] = [Market::Spot, Market::UsdFutures];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Market::Spot => "spot",
+            Market::UsdFutures => "USDⓈ-M futures",
+        }
+    }
+
+    /// Short code used to tell apart files of symbols with the same name on different markets.
+    pub fn code(&self) -> &str {
+        match self {
+            Market::Spot => "spot",
+            Market::UsdFutures => "um",
+        }
+    }
+}
+
+/// Price klines are built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PriceKind {
+    /// Price of the last trade.
+    #[default]
+    Last,
+    /// Futures mark price used for unrealized PnL and liquidations.
+    Mark,
+    /// Futures index price averaged over spot exchanges.
+    Index,
+}
+
+impl PriceKind {
+    pub const ALL: [PriceKind; 3] = [PriceKind::Last, PriceKind::Mark, PriceKind::Index];
+
+    pub fn name(&self) -> &str {
+        match self {
+            PriceKind::Last => "last price",
+            PriceKind::Mark => "mark price",
+            PriceKind::Index => "index price",
+        }
+    }
+}
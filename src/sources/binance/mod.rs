@@ -1,7 +1,28 @@
 mod client;
+mod depth;
+mod filters;
+mod futures;
 mod interval;
+mod market;
+mod rate_limiter;
+mod retry;
+mod stream;
+mod ticker;
+mod trades;
 
 pub use self::client::*;
+pub use self::depth::{Depth, DepthLevel, DEPTH_LIMITS};
+pub use self::filters::{Filter, LotSize, OrderType, PriceFilter};
+pub use self::futures::{
+    FundingRate, OpenInterest, FUNDING_PERIOD_MILLIS, OPEN_INTEREST_HISTORY_MILLIS,
+    OPEN_INTEREST_PERIODS,
+};
 pub use self::interval::*;
+pub use self::market::{Market, PriceKind};
+pub use self::rate_limiter::RateLimiter;
+pub use self::retry::{with_retry, with_retry_async};
+pub use self::stream::{KlineStream, StreamEvent, FUTURES_STREAM_URL, STREAM_URL};
+pub use self::ticker::Ticker24h;
+pub use self::trades::{AggTrade, Trade};
 
 pub mod errors;
@@ -0,0 +1,129 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+/// Binance counts request weight in fixed one minute windows.
+const WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct Usage {
+    used: usize,
+    window_start: Instant,
+    banned_until: Option<Instant>,
+}
+
+/// Tracks request weight used against exchange limit and throttles requests
+/// before exchange starts to reject them with 429 or 418 status.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: usize,
+    usage: Mutex<Usage>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            usage: Mutex::new(Usage {
+                used: 0,
+                window_start: Instant::now(),
+                banned_until: None,
+            }),
+        }
+    }
+
+    /// Blocks until request with the weight can be sent without exceeding the limit.
+    pub fn acquire(&self, weight: usize) {
+        while let Some(wait) = self.reserve(weight) {
+            debug!("throttling request with weight: {weight}; waiting: {wait:?}");
+            thread::sleep(wait);
+        }
+    }
+
+    /// Async version of acquire which does not block the runtime thread while waiting.
+    pub async fn acquire_async(&self, weight: usize) {
+        while let Some(wait) = self.reserve(weight) {
+            debug!("throttling request with weight: {weight}; waiting: {wait:?}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Syncs used weight with the value reported by exchange.
+    pub fn update_used(&self, used: usize) {
+        let mut usage = self.usage.lock().unwrap();
+        if used > usage.used {
+            usage.used = used;
+        }
+    }
+
+    /// Stops all requests for the duration requested by exchange.
+    pub fn ban(&self, duration: Duration) {
+        warn!("exchange requested to stop sending requests for: {duration:?}");
+
+        let until = Instant::now() + duration;
+        let mut usage = self.usage.lock().unwrap();
+        if usage.banned_until < Some(until) {
+            usage.banned_until = Some(until);
+        }
+    }
+
+    /// Reserves weight returning None or returns duration to wait before the next try.
+    fn reserve(&self, weight: usize) -> Option<Duration> {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+
+        if let Some(until) = usage.banned_until {
+            if until > now {
+                return Some(until - now);
+            }
+            usage.banned_until = None;
+        }
+
+        if now.duration_since(usage.window_start) >= WINDOW {
+            usage.window_start = now;
+            usage.used = 0;
+        }
+
+        if usage.used + weight <= self.limit {
+            usage.used += weight;
+            return None;
+        }
+
+        Some(usage.window_start + WINDOW - now)
+    }
+}
+
+#[cfg(test)]
+mod rate_limiter_tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let limiter = RateLimiter::new(10);
+
+        assert_eq!(limiter.reserve(4), None);
+        assert_eq!(limiter.reserve(6), None);
+        assert!(limiter.reserve(1).is_some());
+    }
+
+    #[test]
+    fn test_update_used() {
+        let limiter = RateLimiter::new(10);
+
+        limiter.update_used(9);
+        assert!(limiter.reserve(2).is_some());
+        assert_eq!(limiter.reserve(1), None);
+    }
+
+    #[test]
+    fn test_ban() {
+        let limiter = RateLimiter::new(10);
+
+        limiter.ban(Duration::from_secs(5));
+        let wait = limiter.reserve(1);
+        assert!(wait.is_some());
+        assert!(wait.unwrap() <= Duration::from_secs(5));
+    }
+}
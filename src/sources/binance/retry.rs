@@ -0,0 +1,130 @@
+use std::future::Future;
+use std::thread;
+use std::time::Duration;
+
+use tracing::warn;
+
+use super::errors::ClientError;
+
+/// Calls f until it succeeds or attempts are exhausted.
+///
+/// Delay between attempts grows exponentially starting from base_delay.
+/// Retry-After requested by exchange is respected if it is longer.
+pub fn with_retry<T, F>(attempts: usize, base_delay: Duration, mut f: F) -> Result<T, ClientError>
+where
+    F: FnMut() -> Result<T, ClientError>,
+{
+    let mut attempt = 0;
+    loop {
+        let err = match f() {
+            Ok(res) => return Ok(res),
+            Err(err) => err,
+        };
+
+        attempt += 1;
+        match retry_delay(attempt, attempts, base_delay, &err) {
+            Some(delay) => thread::sleep(delay),
+            None => return Err(err),
+        }
+    }
+}
+
+/// Async version of with_retry which does not block the runtime thread between attempts.
+pub async fn with_retry_async<T, F, Fut>(
+    attempts: usize,
+    base_delay: Duration,
+    mut f: F,
+) -> Result<T, ClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut attempt = 0;
+    loop {
+        let err = match f().await {
+            Ok(res) => return Ok(res),
+            Err(err) => err,
+        };
+
+        attempt += 1;
+        match retry_delay(attempt, attempts, base_delay, &err) {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => return Err(err),
+        }
+    }
+}
+
+/// Returns delay before the next attempt or None if the error should be returned.
+fn retry_delay(
+    attempt: usize,
+    attempts: usize,
+    base_delay: Duration,
+    err: &ClientError,
+) -> Option<Duration> {
+    if attempt >= attempts || !err.retryable() {
+        return None;
+    }
+
+    let mut delay = base_delay * 2u32.pow(attempt as u32 - 1);
+    if let ClientError::RateLimited(Some(retry_after)) = err {
+        delay = delay.max(Duration::from_secs(*retry_after));
+    }
+
+    warn!("request failed: {err}; attempt: {attempt}/{attempts}; retrying in {delay:?}");
+    Some(delay)
+}
+
+#[cfg(test)]
+mod retry_tests {
+    use super::*;
+
+    #[test]
+    fn test_with_retry() {
+        let mut calls = 0;
+        let res = with_retry(3, Duration::from_millis(1), || {
+            calls += 1;
+            if calls < 3 {
+                return Err(ClientError::Status(500));
+            }
+
+            Ok(calls)
+        });
+        assert_eq!(res.unwrap(), 3);
+
+        let mut calls = 0;
+        let res: Result<(), ClientError> = with_retry(3, Duration::from_millis(1), || {
+            calls += 1;
+            Err(ClientError::Status(500))
+        });
+        assert!(res.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_async() {
+        let mut calls = 0;
+        let res = with_retry_async(3, Duration::from_millis(1), || {
+            calls += 1;
+            let calls = calls;
+            async move {
+                match calls {
+                    1 => Err(ClientError::RateLimited(None)),
+                    _ => Ok(calls),
+                }
+            }
+        })
+        .await;
+        assert_eq!(res.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_with_retry_not_retryable() {
+        let mut calls = 0;
+        let res: Result<(), ClientError> = with_retry(3, Duration::from_millis(1), || {
+            calls += 1;
+            Err(ClientError::Status(400))
+        });
+        assert!(res.is_err());
+        assert_eq!(calls, 1);
+    }
+}
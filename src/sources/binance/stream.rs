@@ -0,0 +1,271 @@
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam::channel::Sender;
+use serde::Deserialize;
+use tracing::{error, info, warn};
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+use super::{client::parse_number, errors::ClientError, Interval, Kline};
+
+pub const STREAM_URL: &str = "wss://stream.binance.com:9443";
+pub const FUTURES_STREAM_URL: &str = "wss://fstream.binance.com";
+
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum StreamEvent {
+    /// Sent on every (re)connect. Klines since the last received one may be missed
+    /// and have to be backfilled through the rest api.
+    Connected,
+    Disconnected(String),
+    /// Klines to upsert by open time. The last one may be not closed yet.
+    Klines(Vec<Kline>),
+}
+
+#[derive(Deserialize)]
+struct KlineEvent {
+    #[serde(rename = "k")]
+    kline: KlineEventData,
+}
+
+#[derive(Deserialize)]
+struct KlineEventData {
+    #[serde(rename = "t")]
+    t_open: i64,
+    #[serde(rename = "T")]
+    t_close: i64,
+    #[serde(rename = "o")]
+    open: String,
+    #[serde(rename = "h")]
+    high: String,
+    #[serde(rename = "l")]
+    low: String,
+    #[serde(rename = "c")]
+    close: String,
+    #[serde(rename = "v")]
+    volume: String,
+    #[serde(rename = "n")]
+    number_of_trades: i64,
+    #[serde(rename = "q")]
+    quote_asset_volume: String,
+    #[serde(rename = "V")]
+    taker_buy_base_asset_volume: String,
+    #[serde(rename = "Q")]
+    taker_buy_quote_asset_volume: String,
+}
+
+impl KlineEventData {
+    fn to_kline(&self) -> Result<Kline, ClientError> {
+        Ok(Kline {
+            t_open: self.t_open,
+            open: parse_number(&self.open)?,
+            high: parse_number(&self.high)?,
+            low: parse_number(&self.low)?,
+            close: parse_number(&self.close)?,
+            volume: parse_number(&self.volume)?,
+            t_close: self.t_close,
+            quote_asset_volume: parse_number(&self.quote_asset_volume)?,
+            number_of_trades: self.number_of_trades,
+            taker_buy_base_asset_volume: parse_number(&self.taker_buy_base_asset_volume)?,
+            taker_buy_quote_asset_volume: parse_number(&self.taker_buy_quote_asset_volume)?,
+        })
+    }
+}
+
+/// Subscription to binance kline websocket stream.
+///
+/// Reconnects when connection drops. Klines missed while disconnected are not resent
+/// so receiver backfills them on every connect.
+pub struct KlineStream {
+    url: String,
+    stop: Arc<AtomicBool>,
+}
+
+impl KlineStream {
+    pub fn new(base_url: &str, symbol: String, interval: Interval) -> Self {
+        let url = format!(
+            "{base_url}/ws/{}@kline_{}",
+            symbol.to_lowercase(),
+            interval.as_str()
+        );
+
+        Self {
+            url,
+            stop: Default::default(),
+        }
+    }
+
+    /// Returns flag which stops the stream when set.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Reads the stream until stopped. Blocks the calling thread.
+    pub fn run(&self, sender: Sender<StreamEvent>) {
+        let mut delay = RECONNECT_BASE_DELAY;
+
+        while !self.stopped() {
+            info!("connecting to kline stream: {}", self.url);
+            let mut socket = match self.connect() {
+                Ok(socket) => socket,
+                Err(err) => {
+                    error!("failed to connect to kline stream: {err}");
+                    self.send(&sender, StreamEvent::Disconnected(err));
+                    self.sleep(delay);
+                    delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+                    continue;
+                }
+            };
+
+            delay = RECONNECT_BASE_DELAY;
+            self.send(&sender, StreamEvent::Connected);
+
+            let reason = self.read(&mut socket, &sender);
+            let _ = socket.close(None);
+
+            if let Some(reason) = reason {
+                warn!("kline stream disconnected: {reason}");
+                self.send(&sender, StreamEvent::Disconnected(reason));
+                self.sleep(delay);
+            }
+        }
+
+        info!("kline stream stopped: {}", self.url);
+    }
+
+    fn connect(&self) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, String> {
+        let (socket, _) = tungstenite::connect(self.url.as_str()).map_err(|err| err.to_string())?;
+
+        // read timeout lets the stream check stop flag while waiting for messages
+        let tcp = match socket.get_ref() {
+            MaybeTlsStream::Plain(s) => s,
+            MaybeTlsStream::NativeTls(s) => s.get_ref(),
+            _ => return Ok(socket),
+        };
+        tcp.set_read_timeout(Some(READ_TIMEOUT))
+            .map_err(|err| err.to_string())?;
+
+        Ok(socket)
+    }
+
+    /// Reads messages until stopped or disconnected. Returns disconnect reason.
+    fn read(
+        &self,
+        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+        sender: &Sender<StreamEvent>,
+    ) -> Option<String> {
+        while !self.stopped() {
+            let msg = match socket.read_message() {
+                Ok(msg) => msg,
+                Err(tungstenite::Error::Io(err))
+                    if err.kind() == ErrorKind::WouldBlock || err.kind() == ErrorKind::TimedOut =>
+                {
+                    continue;
+                }
+                Err(err) => return Some(err.to_string()),
+            };
+
+            match msg {
+                Message::Text(text) => match parse_kline_event(&text) {
+                    Ok(kline) => self.send(sender, StreamEvent::Klines(vec![kline])),
+                    Err(err) => error!("failed to parse kline event: {err}; event: {text}"),
+                },
+                Message::Close(frame) => return Some(format!("closed by server: {frame:?}")),
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn send(&self, sender: &Sender<StreamEvent>, event: StreamEvent) {
+        if self.stopped() {
+            return;
+        }
+
+        if let Err(err) = sender.send(event) {
+            error!("failed to send stream event: {err}");
+        }
+    }
+
+    fn sleep(&self, delay: Duration) {
+        let step = Duration::from_millis(100);
+        let mut slept = Duration::ZERO;
+        while slept < delay && !self.stopped() {
+            thread::sleep(step);
+            slept += step;
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
+
+fn parse_kline_event(text: &str) -> Result<Kline, ClientError> {
+    let event = serde_json::from_str::<KlineEvent>(text)?;
+    event.kline.to_kline()
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use std::net::TcpListener;
+
+    use crossbeam::channel::unbounded;
+
+    use super::*;
+
+    const EVENT: &str = r#"{"e":"kline","E":123456789,"s":"BNBBTC","k":{"t":123400000,"T":123460000,"s":"BNBBTC","i":"1m","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}}"#;
+
+    #[test]
+    fn test_parse_kline_event() {
+        let kline = parse_kline_event(EVENT).unwrap();
+
+        assert_eq!(kline.t_open, 123400000);
+        assert_eq!(kline.t_close, 123460000);
+        assert_eq!(kline.close, 0.002);
+        assert_eq!(kline.number_of_trades, 100);
+    }
+
+    #[test]
+    fn test_run() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (tcp, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(tcp).unwrap();
+            socket
+                .write_message(Message::Text(EVENT.to_string()))
+                .unwrap();
+            thread::sleep(Duration::from_secs(1));
+        });
+
+        let stream = KlineStream::new(
+            &format!("ws://{addr}"),
+            "BNBBTC".to_string(),
+            Interval::Minute,
+        );
+        let stop = stream.stop_handle();
+        let (s, r) = unbounded();
+        let handle = thread::spawn(move || stream.run(s));
+
+        assert!(matches!(
+            r.recv_timeout(Duration::from_secs(5)),
+            Ok(StreamEvent::Connected)
+        ));
+        match r.recv_timeout(Duration::from_secs(5)) {
+            Ok(StreamEvent::Klines(klines)) => assert_eq!(klines[0].t_open, 123400000),
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        handle.join().unwrap();
+    }
+}
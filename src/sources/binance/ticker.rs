@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::{client::parse_number, errors::ClientError};
+
+/// Price change statistics of a symbol for the last 24 hours.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ticker24h {
+    pub symbol: String,
+    pub last_price: f64,
+    pub price_change_percent: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub count: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Ticker24hData {
+    symbol: String,
+    last_price: String,
+    price_change_percent: String,
+    volume: String,
+    quote_volume: String,
+    count: i64,
+}
+
+impl Ticker24h {
+    pub(super) fn from_ticker_data(data: Ticker24hData) -> Result<Self, ClientError> {
+        Ok(Ticker24h {
+            symbol: data.symbol,
+            last_price: parse_number(&data.last_price)?,
+            price_change_percent: parse_number(&data.price_change_percent)?,
+            volume: parse_number(&data.volume)?,
+            quote_volume: parse_number(&data.quote_volume)?,
+            count: data.count,
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use super::{client::parse_number, errors::ClientError};
+
+/// Single trade from exchange history.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Trade {
+    pub id: i64,
+    pub price: f64,
+    pub qty: f64,
+    pub quote_qty: f64,
+    pub time: i64,
+    pub buyer_maker: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TradeData {
+    id: i64,
+    price: String,
+    qty: String,
+    #[serde(rename = "quoteQty")]
+    quote_qty: String,
+    time: i64,
+    #[serde(rename = "isBuyerMaker")]
+    buyer_maker: bool,
+}
+
+impl Trade {
+    pub(super) fn from_trade_data(data: TradeData) -> Result<Self, ClientError> {
+        Ok(Trade {
+            id: data.id,
+            price: parse_number(&data.price)?,
+            qty: parse_number(&data.qty)?,
+            quote_qty: parse_number(&data.quote_qty)?,
+            time: data.time,
+            buyer_maker: data.buyer_maker,
+        })
+    }
+}
+
+/// Trades filled at the same time, price and side compressed into one.
+#[derive(PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AggTrade {
+    pub id: i64,
+    pub price: f64,
+    pub qty: f64,
+    pub first_trade_id: i64,
+    pub last_trade_id: i64,
+    pub time: i64,
+    pub buyer_maker: bool,
+}
+
+impl AggTrade {
+    /// Taker is a buyer when the maker is a seller.
+    pub fn taker_buy(&self) -> bool {
+        !self.buyer_maker
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct AggTradeData {
+    #[serde(rename = "a")]
+    id: i64,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    qty: String,
+    #[serde(rename = "f")]
+    first_trade_id: i64,
+    #[serde(rename = "l")]
+    last_trade_id: i64,
+    #[serde(rename = "T")]
+    time: i64,
+    #[serde(rename = "m")]
+    buyer_maker: bool,
+}
+
+impl AggTrade {
+    pub(super) fn from_agg_trade_data(data: AggTradeData) -> Result<Self, ClientError> {
+        Ok(AggTrade {
+            id: data.id,
+            price: parse_number(&data.price)?,
+            qty: parse_number(&data.qty)?,
+            first_trade_id: data.first_trade_id,
+            last_trade_id: data.last_trade_id,
+            time: data.time,
+            buyer_maker: data.buyer_maker,
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use tracing::{error, info};
+
+use super::binance::errors::ClientError;
+use super::MarketDataSource;
+
+const SYNC_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+lazy_static! {
+    static ref LAST_SYNC: RwLock<Option<ClockSync>> = RwLock::new(None);
+}
+
+/// Result of measuring the difference between exchange server clock and local clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSync {
+    /// Millis to add to local time to get server time.
+    pub offset: i64,
+    /// Round trip time of the server time request in millis.
+    pub rtt: i64,
+    /// Local time in millis the offset was measured at.
+    pub synced_at: i64,
+}
+
+impl ClockSync {
+    /// Estimates offset assuming the server read its clock in the middle of the request.
+    fn measure(sent_at: i64, server_time: i64, received_at: i64) -> Self {
+        let rtt = received_at - sent_at;
+        Self {
+            offset: server_time - (sent_at + rtt / 2),
+            rtt,
+            synced_at: received_at,
+        }
+    }
+}
+
+/// Last measured offset or None if the clock was never synced.
+pub fn last_sync() -> Option<ClockSync> {
+    *LAST_SYNC.read().unwrap()
+}
+
+/// Exchange aligned current time. Falls back to local time until the first sync.
+pub fn now() -> DateTime<Utc> {
+    let offset = last_sync().map(|s| s.offset).unwrap_or_default();
+    Utc::now() + chrono::Duration::milliseconds(offset)
+}
+
+pub fn now_millis() -> i64 {
+    now().timestamp_millis()
+}
+
+/// Requests server time and stores measured offset.
+pub fn sync(source: &dyn MarketDataSource) -> Result<ClockSync, ClientError> {
+    let sent_at = Utc::now().timestamp_millis();
+    let server_time = source.server_time()?;
+    let received_at = Utc::now().timestamp_millis();
+
+    let measured = ClockSync::measure(sent_at, server_time, received_at);
+    info!(
+        "synced server clock: offset: {}ms, rtt: {}ms",
+        measured.offset, measured.rtt
+    );
+
+    *LAST_SYNC.write().unwrap() = Some(measured);
+    Ok(measured)
+}
+
+/// Syncs the clock in background repeating it periodically to follow local clock drift.
+pub fn spawn_sync(source: Arc<dyn MarketDataSource>) -> JoinHandle<()> {
+    thread::spawn(move || loop {
+        if let Err(err) = sync(source.as_ref()) {
+            error!("failed to sync server clock: {err}");
+        }
+
+        thread::sleep(SYNC_PERIOD);
+    })
+}
+
+#[cfg(test)]
+mod clock_tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let measured = ClockSync::measure(1000, 1600, 1200);
+
+        assert_eq!(measured.rtt, 200);
+        assert_eq!(measured.offset, 500);
+        assert_eq!(measured.synced_at, 1200);
+
+        let measured = ClockSync::measure(1000, 900, 1100);
+        assert_eq!(measured.offset, -150);
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use tokio::task::spawn_blocking;
+
+use super::binance::{
+    errors::ClientError, AggTrade, Depth, Info, Interval, Kline, PriceKind, Ticker24h,
+};
+use super::{MarketDataSource, SourceFuture};
+
+type KlineFn = Box<dyn Fn(i64, usize) -> Result<Vec<Kline>, ClientError> + Send + Sync>;
+type KlineAsyncFn = Box<dyn Fn(i64, usize) -> SourceFuture<Vec<Kline>> + Send + Sync>;
+type AggTradesFn =
+    Box<dyn Fn(i64, i64, Option<i64>, usize) -> Result<Vec<AggTrade>, ClientError> + Send + Sync>;
+
+/// Source for tests answering with the given functions.
+///
+/// Requests without a function return empty data.
+#[derive(Default)]
+pub struct FakeSource {
+    kline: Option<KlineFn>,
+    kline_async: Option<KlineAsyncFn>,
+    agg_trades: Option<AggTradesFn>,
+}
+
+impl FakeSource {
+    /// Answers klines requests by start time and limit.
+    pub fn with_kline(
+        mut self,
+        f: impl Fn(i64, usize) -> Result<Vec<Kline>, ClientError> + Send + Sync + 'static,
+    ) -> Self {
+        self.kline = Some(Box::new(f));
+        self
+    }
+
+    /// Answers async klines requests by start time and limit without the blocking pool.
+    pub fn with_kline_async(
+        mut self,
+        f: impl Fn(i64, usize) -> SourceFuture<Vec<Kline>> + Send + Sync + 'static,
+    ) -> Self {
+        self.kline_async = Some(Box::new(f));
+        self
+    }
+
+    /// Answers aggregated trades requests by start time, end time, from id and limit.
+    pub fn with_agg_trades(
+        mut self,
+        f: impl Fn(i64, i64, Option<i64>, usize) -> Result<Vec<AggTrade>, ClientError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.agg_trades = Some(Box::new(f));
+        self
+    }
+}
+
+impl MarketDataSource for FakeSource {
+    fn kline(
+        &self,
+        _symbol: String,
+        _interval: Interval,
+        start_time: i64,
+        limit: usize,
+    ) -> Result<Vec<Kline>, ClientError> {
+        match &self.kline {
+            Some(f) => f(start_time, limit),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn price_kline_async(
+        self: Arc<Self>,
+        symbol: String,
+        interval: Interval,
+        kind: PriceKind,
+        start_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<Kline>> {
+        match &self.kline_async {
+            Some(f) => f(start_time, limit),
+            None => Box::pin(async move {
+                spawn_blocking(move || self.price_kline(symbol, interval, kind, start_time, limit))
+                    .await?
+            }),
+        }
+    }
+
+    fn agg_trades(
+        &self,
+        _symbol: String,
+        start_time: i64,
+        end_time: i64,
+        from_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AggTrade>, ClientError> {
+        match &self.agg_trades {
+            Some(f) => f(start_time, end_time, from_id, limit),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn info(&self) -> Result<Info, ClientError> {
+        Ok(Info::default())
+    }
+
+    fn server_time(&self) -> Result<i64, ClientError> {
+        Ok(0)
+    }
+
+    fn depth(&self, _symbol: String, _limit: usize) -> Result<Depth, ClientError> {
+        Ok(Depth::default())
+    }
+
+    fn ticker_24h(&self) -> Result<Vec<Ticker24h>, ClientError> {
+        Ok(vec![])
+    }
+}
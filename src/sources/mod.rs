@@ -1 +1,11 @@
+mod async_client;
 pub mod binance;
+pub mod clock;
+#[cfg(test)]
+mod fake_source;
+mod source;
+
+pub use self::async_client::{AsyncClient, Download, Tagged};
+#[cfg(test)]
+pub use self::fake_source::FakeSource;
+pub use self::source::{MarketDataSource, SourceFuture};
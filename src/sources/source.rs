@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio::task::spawn_blocking;
+
+use super::binance::{
+    errors::ClientError, AggTrade, Depth, FundingRate, Info, Interval, Kline, OpenInterest,
+    PriceKind, Ticker24h,
+};
+
+/// Request of a source running on the tokio runtime. Dropping it aborts the request.
+pub type SourceFuture<T> = Pin<Box<dyn Future<Output = Result<T, ClientError>> + Send>>;
+
+/// Provider of market data used by candles widgets.
+///
+/// Implementations must be cheap to share between download threads.
+pub trait MarketDataSource: Send + Sync + 'static {
+    /// Returns up to limit klines starting from start_time.
+    fn kline(
+        &self,
+        symbol: String,
+        interval: Interval,
+        start_time: i64,
+        limit: usize,
+    ) -> Result<Vec<Kline>, ClientError>;
+
+    /// Returns up to limit aggregated trades.
+    ///
+    /// If from_id is set trades are returned starting from that id and time range is ignored.
+    /// Otherwise trades are taken from the range between start_time and end_time
+    /// which must be shorter than an hour.
+    fn agg_trades(
+        &self,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        from_id: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<AggTrade>, ClientError>;
+
+    /// Returns exchange symbols info.
+    fn info(&self) -> Result<Info, ClientError>;
+
+    /// Returns exchange time in millis.
+    fn server_time(&self) -> Result<i64, ClientError>;
+
+    /// Returns order book snapshot with up to limit levels on each side.
+    fn depth(&self, symbol: String, limit: usize) -> Result<Depth, ClientError>;
+
+    /// Returns 24 hour price change statistics for all symbols.
+    fn ticker_24h(&self) -> Result<Vec<Ticker24h>, ClientError>;
+
+    /// Returns up to limit klines of the price kind starting from start_time.
+    ///
+    /// Mark and index price klines exist only on futures markets.
+    fn price_kline(
+        &self,
+        symbol: String,
+        interval: Interval,
+        kind: PriceKind,
+        start_time: i64,
+        limit: usize,
+    ) -> Result<Vec<Kline>, ClientError> {
+        match kind {
+            PriceKind::Last => self.kline(symbol, interval, start_time, limit),
+            _ => Err(ClientError::Unsupported(kind.name().to_string())),
+        }
+    }
+
+    /// Async version of price_kline.
+    ///
+    /// By default blocking price_kline runs on the blocking pool and can not be aborted.
+    fn price_kline_async(
+        self: Arc<Self>,
+        symbol: String,
+        interval: Interval,
+        kind: PriceKind,
+        start_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<Kline>> {
+        Box::pin(async move {
+            spawn_blocking(move || self.price_kline(symbol, interval, kind, start_time, limit))
+                .await?
+        })
+    }
+
+    /// Returns up to limit funding events of a perpetual contract between start_time and end_time.
+    fn funding_rate(
+        &self,
+        _symbol: String,
+        _start_time: i64,
+        _end_time: i64,
+        _limit: usize,
+    ) -> Result<Vec<FundingRate>, ClientError> {
+        Err(ClientError::Unsupported("funding rate".to_string()))
+    }
+
+    /// Async version of funding_rate.
+    ///
+    /// By default blocking funding_rate runs on the blocking pool and can not be aborted.
+    fn funding_rate_async(
+        self: Arc<Self>,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<FundingRate>> {
+        Box::pin(async move {
+            spawn_blocking(move || self.funding_rate(symbol, start_time, end_time, limit)).await?
+        })
+    }
+
+    /// Returns up to limit open interest statistics aggregated by period.
+    ///
+    /// Binance keeps only the last 30 days of statistics.
+    fn open_interest_hist(
+        &self,
+        _symbol: String,
+        _period: Interval,
+        _start_time: i64,
+        _end_time: i64,
+        _limit: usize,
+    ) -> Result<Vec<OpenInterest>, ClientError> {
+        Err(ClientError::Unsupported("open interest".to_string()))
+    }
+
+    /// Async version of open_interest_hist.
+    ///
+    /// By default blocking open_interest_hist runs on the blocking pool and can not be aborted.
+    fn open_interest_hist_async(
+        self: Arc<Self>,
+        symbol: String,
+        period: Interval,
+        start_time: i64,
+        end_time: i64,
+        limit: usize,
+    ) -> SourceFuture<Vec<OpenInterest>> {
+        Box::pin(async move {
+            spawn_blocking(move || {
+                self.open_interest_hist(symbol, period, start_time, end_time, limit)
+            })
+            .await?
+        })
+    }
+}
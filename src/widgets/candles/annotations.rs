@@ -0,0 +1,330 @@
+use egui::{plot::BoxElem, ComboBox, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+/// Max distance in points between the pointer and an annotation for the eraser to pick it.
+const ERASE_DISTANCE: f64 = 10.0;
+
+/// Mark up drawn over candles in plot coordinates: x is time in millis, y is price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Annotation {
+    TrendLine {
+        from: [f64; 2],
+        to: [f64; 2],
+    },
+    /// Horizontal price level.
+    Level {
+        price: f64,
+    },
+    /// Rectangle between two corners marking a price and time range.
+    Range {
+        from: [f64; 2],
+        to: [f64; 2],
+    },
+    Note {
+        at: [f64; 2],
+        text: String,
+    },
+}
+
+impl Annotation {
+    /// Distance to the pointer in screen points.
+    fn distance(&self, pointer: [f64; 2], to_screen: &impl Fn([f64; 2]) -> [f64; 2]) -> f64 {
+        let p = to_screen(pointer);
+        match self {
+            Annotation::TrendLine { from, to } => {
+                segment_distance(p, to_screen(*from), to_screen(*to))
+            }
+            Annotation::Level { price } => (to_screen([pointer[0], *price])[1] - p[1]).abs(),
+            Annotation::Range { from, to } => {
+                let (a, b) = (to_screen(*from), to_screen(*to));
+                let dx = (a[0].min(b[0]) - p[0]).max(p[0] - a[0].max(b[0])).max(0.0);
+                let dy = (a[1].min(b[1]) - p[1]).max(p[1] - a[1].max(b[1])).max(0.0);
+                dx.hypot(dy)
+            }
+            Annotation::Note { at, .. } => {
+                let at = to_screen(*at);
+                (at[0] - p[0]).hypot(at[1] - p[1])
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tool {
+    /// Plot is dragged and zoomed as usual.
+    #[default]
+    Pan,
+    TrendLine,
+    Level,
+    Range,
+    Note,
+    Erase,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 6] = [
+        Tool::Pan,
+        Tool::TrendLine,
+        Tool::Level,
+        Tool::Range,
+        Tool::Note,
+        Tool::Erase,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Tool::Pan => "pan",
+            Tool::TrendLine => "trend line",
+            Tool::Level => "level",
+            Tool::Range => "range",
+            Tool::Note => "note",
+            Tool::Erase => "erase",
+        }
+    }
+
+    /// Number of clicks needed to place the annotation.
+    fn clicks(&self) -> usize {
+        match self {
+            Tool::TrendLine | Tool::Range => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Annotations of the shown symbol together with the drawing state.
+///
+/// Shared between props and drawer clones so drawing survives drawer updates.
+#[derive(Debug, Clone)]
+pub struct Annotations {
+    items: Vec<Annotation>,
+    tool: Tool,
+    /// First point of a two click annotation.
+    pending: Option<[f64; 2]>,
+    note: String,
+    snap: bool,
+    changed: bool,
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Self {
+            items: Default::default(),
+            tool: Default::default(),
+            pending: Default::default(),
+            note: Default::default(),
+            snap: true,
+            changed: Default::default(),
+        }
+    }
+}
+
+impl Annotations {
+    pub fn items(&self) -> &[Annotation] {
+        &self.items
+    }
+
+    /// Replaces annotations, e.g. with the ones loaded for another symbol.
+    pub fn set_items(&mut self, items: Vec<Annotation>) {
+        self.items = items;
+        self.pending = None;
+        self.changed = false;
+    }
+
+    pub fn tool(&self) -> Tool {
+        self.tool
+    }
+
+    pub fn pending(&self) -> Option<[f64; 2]> {
+        self.pending
+    }
+
+    pub fn snap(&self) -> bool {
+        self.snap
+    }
+
+    /// Returns true once after annotations were added or removed.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Applies the tool to the clicked point.
+    pub fn click(&mut self, p: [f64; 2], to_screen: impl Fn([f64; 2]) -> [f64; 2]) {
+        if self.tool.clicks() == 2 && self.pending.is_none() {
+            self.pending = Some(p);
+            return;
+        }
+
+        let annotation = match self.tool {
+            Tool::Pan => return,
+            Tool::TrendLine => Annotation::TrendLine {
+                from: self.pending.take().unwrap_or(p),
+                to: p,
+            },
+            Tool::Level => Annotation::Level { price: p[1] },
+            Tool::Range => Annotation::Range {
+                from: self.pending.take().unwrap_or(p),
+                to: p,
+            },
+            Tool::Note => match self.note.trim().is_empty() {
+                true => return,
+                false => Annotation::Note {
+                    at: p,
+                    text: self.note.trim().to_string(),
+                },
+            },
+            Tool::Erase => {
+                if let Some(idx) = self.nearest(p, &to_screen) {
+                    self.items.remove(idx);
+                    self.changed = true;
+                }
+                return;
+            }
+        };
+
+        self.items.push(annotation);
+        self.changed = true;
+    }
+
+    fn nearest(&self, p: [f64; 2], to_screen: &impl Fn([f64; 2]) -> [f64; 2]) -> Option<usize> {
+        self.items
+            .iter()
+            .map(|a| a.distance(p, to_screen))
+            .enumerate()
+            .filter(|(_, d)| *d <= ERASE_DISTANCE)
+            .min_by(|(_, l), (_, r)| l.total_cmp(r))
+            .map(|(i, _)| i)
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut Ui) {
+        let mut tool = self.tool;
+        ComboBox::from_id_source("graph_drawing_tool")
+            .selected_text(tool.name())
+            .show_ui(ui, |ui| {
+                Tool::ALL.iter().for_each(|t| {
+                    ui.selectable_value(&mut tool, *t, t.name());
+                });
+            });
+        if tool != self.tool {
+            self.tool = tool;
+            self.pending = None;
+        }
+
+        if self.tool == Tool::Note {
+            ui.add(
+                TextEdit::singleline(&mut self.note)
+                    .hint_text("note text")
+                    .desired_width(120.0),
+            );
+        }
+
+        if self.tool != Tool::Pan {
+            ui.checkbox(&mut self.snap, "snap")
+                .on_hover_text("Snap points to open, high, low or close of the candle");
+        }
+
+        if !self.items.is_empty() && ui.button("clear drawings").clicked() {
+            self.items.clear();
+            self.pending = None;
+            self.changed = true;
+        }
+    }
+}
+
+/// Moves the point to the closest open, high, low or close of the candle under it.
+///
+/// Points outside of candles are left as is so lines can be extended into the future.
+pub fn snap(p: [f64; 2], candles: &[BoxElem]) -> [f64; 2] {
+    let idx = candles.partition_point(|el| el.argument < p[0]);
+    let nearest = [idx.checked_sub(1), Some(idx)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| candles.get(i))
+        .min_by(|l, r| {
+            (l.argument - p[0])
+                .abs()
+                .total_cmp(&(r.argument - p[0]).abs())
+        });
+
+    match nearest {
+        Some(el) if (el.argument - p[0]).abs() <= el.box_width.abs() / 2.0 => {
+            let spread = &el.spread;
+            let y = [
+                spread.lower_whisker,
+                spread.quartile1,
+                spread.quartile3,
+                spread.upper_whisker,
+            ]
+            .into_iter()
+            .min_by(|l, r| (l - p[1]).abs().total_cmp(&(r - p[1]).abs()))
+            .unwrap_or(p[1]);
+
+            [el.argument, y]
+        }
+        _ => p,
+    }
+}
+
+fn segment_distance(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len = dx * dx + dy * dy;
+    let t = match len > 0.0 {
+        true => (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+
+    (a[0] + t * dx - p[0]).hypot(a[1] + t * dy - p[1])
+}
+
+#[cfg(test)]
+mod annotations_tests {
+    use egui::plot::BoxSpread;
+
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        let candles = vec![
+            BoxElem::new(30.0, BoxSpread::new(1.0, 2.0, 2.0, 4.0, 5.0)).box_width(-54.0),
+            BoxElem::new(90.0, BoxSpread::new(10.0, 11.0, 11.0, 12.0, 13.0)).box_width(-54.0),
+        ];
+
+        assert_eq!(snap([35.0, 3.9], &candles), [30.0, 4.0]);
+        assert_eq!(snap([80.0, 0.0], &candles), [90.0, 10.0]);
+        assert_eq!(snap([200.0, 7.0], &candles), [200.0, 7.0]);
+    }
+
+    #[test]
+    fn test_click() {
+        let to_screen = |p: [f64; 2]| p;
+        let mut annotations = Annotations::default();
+
+        annotations.tool = Tool::TrendLine;
+        annotations.click([0.0, 1.0], to_screen);
+        assert_eq!(annotations.pending(), Some([0.0, 1.0]));
+        assert!(!annotations.take_changed());
+
+        annotations.click([10.0, 2.0], to_screen);
+        assert_eq!(
+            annotations.items(),
+            &[Annotation::TrendLine {
+                from: [0.0, 1.0],
+                to: [10.0, 2.0]
+            }]
+        );
+        assert!(annotations.take_changed());
+
+        annotations.tool = Tool::Note;
+        annotations.click([5.0, 5.0], to_screen);
+        assert_eq!(annotations.items().len(), 1);
+
+        annotations.tool = Tool::Level;
+        annotations.click([5.0, 50.0], to_screen);
+
+        annotations.tool = Tool::Erase;
+        annotations.click([5.0, 2.0], to_screen);
+        assert_eq!(annotations.items(), &[Annotation::Level { price: 50.0 }]);
+
+        annotations.click([5.0, 80.0], to_screen);
+        assert_eq!(annotations.items().len(), 1);
+    }
+}
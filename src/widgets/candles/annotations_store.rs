@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use crate::sources::binance::Market;
+
+use super::{annotations::Annotation, error::CacheError, json_store::JsonStore};
+
+/// Annotations are user data so they are kept apart from the cache which may be wiped.
+const ANNOTATIONS_DIR: &str = "annotations";
+
+/// On disk storage of chart annotations, one file per market and symbol.
+#[derive(Debug, Clone)]
+pub struct AnnotationsStore {
+    store: JsonStore,
+}
+
+impl Default for AnnotationsStore {
+    fn default() -> Self {
+        Self::new(PathBuf::from(ANNOTATIONS_DIR))
+    }
+}
+
+impl AnnotationsStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            store: JsonStore::new(dir).pretty(),
+        }
+    }
+
+    /// Returns saved annotations or empty list if there are none or file is unreadable.
+    pub fn load(&self, market: Market, symbol: &str) -> Vec<Annotation> {
+        self.store
+            .load(&AnnotationsStore::name(market, symbol))
+            .unwrap_or_default()
+    }
+
+    pub fn save(
+        &self,
+        market: Market,
+        symbol: &str,
+        items: &[Annotation],
+    ) -> Result<(), CacheError> {
+        self.store
+            .save(&AnnotationsStore::name(market, symbol), &items)
+    }
+
+    fn name(market: Market, symbol: &str) -> String {
+        format!("{}_{}", market.code(), symbol.to_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod annotations_store_tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_save_load() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let store = AnnotationsStore::new(dir.clone());
+
+        assert!(store.load(Market::Spot, "BTCUSDT").is_empty());
+
+        let items = vec![
+            Annotation::Level { price: 42.5 },
+            Annotation::Note {
+                at: [1.0, 2.0],
+                text: "breakout".to_string(),
+            },
+        ];
+        store.save(Market::Spot, "BTCUSDT", &items).unwrap();
+
+        assert_eq!(store.load(Market::Spot, "btcusdt"), items);
+        assert!(store.load(Market::UsdFutures, "BTCUSDT").is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
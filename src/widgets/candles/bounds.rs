@@ -1,6 +1,8 @@
 use std::cmp::{max, min, Ordering};
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Bounds(pub i64, pub i64);
 
 impl Bounds {
@@ -30,6 +32,10 @@ impl Bounds {
         (self.1 - self.0) as usize
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.1 <= self.0
+    }
+
     pub fn subtract(&self, other: &Bounds) -> Option<BoundsSet> {
         if !self.intersects(other) {
             return Some(BoundsSet::new(vec![*self]));
@@ -57,7 +63,7 @@ impl Bounds {
                 res = res.concat(&right_b);
             }
 
-            if res.len() == 0 {
+            if res.is_empty() {
                 return None;
             }
 
@@ -232,7 +238,7 @@ mod bounds_tests {
     }
 }
 
-#[derive(Default, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Default, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct BoundsSet {
     vals: Vec<Bounds>,
 }
@@ -250,6 +256,10 @@ impl BoundsSet {
         self.vals.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
     pub fn concat(&self, other: &Self) -> Self {
         let mut vals = self.vals.clone();
         vals.extend_from_slice(&other.vals);
@@ -267,10 +277,39 @@ impl BoundsSet {
         self.merge(&BoundsSet::new(vec![o]))
     }
 
+    /// Checks if ts is inside any of the bounds.
+    pub fn contains(&self, ts: i64) -> bool {
+        self.vals.iter().any(|b| b.0 <= ts && ts <= b.1)
+    }
+
     pub fn left_edge(&self) -> Option<i64> {
         Some(self.vals.first()?.0)
     }
 
+    /// Cuts bounds at edge dropping everything after it.
+    pub fn clip_right(&self, edge: i64) -> Self {
+        Self {
+            vals: self
+                .vals
+                .iter()
+                .filter(|b| b.0 < edge)
+                .map(|b| Bounds(b.0, b.1.min(edge)))
+                .collect(),
+        }
+    }
+
+    /// Keeps only parts of bounds covered by other.
+    pub fn intersect(&self, other: &BoundsSet) -> Self {
+        let vals = self
+            .vals
+            .iter()
+            .flat_map(|b| other.vals.iter().filter_map(move |o| b.intersect(o)))
+            .filter(|b| b.0 < b.1)
+            .collect();
+
+        BoundsSet::new(vals).merge(&BoundsSet::default())
+    }
+
     /// Concats, sorts and unions 2 bounds sequences.
     pub fn merge(&self, other: &BoundsSet) -> Self {
         let mut new_vals = self.concat(other).vals;
@@ -299,7 +338,7 @@ impl BoundsSet {
 
     /// Computes self - other difference.
     pub fn subtract(&self, other: &BoundsSet) -> Option<BoundsSet> {
-        if other.len() == 0 {
+        if other.is_empty() {
             return Some(self.clone());
         }
 
@@ -314,7 +353,7 @@ impl BoundsSet {
             res = curr_vals;
         });
 
-        if res.len() == 0 {
+        if res.is_empty() {
             return None;
         }
 
@@ -326,6 +365,32 @@ impl BoundsSet {
 mod bounds_sequence_tests {
     use super::*;
 
+    #[test]
+    fn test_clip_right() {
+        let set = BoundsSet::new(vec![Bounds(0, 10), Bounds(20, 30), Bounds(40, 50)]);
+
+        assert_eq!(
+            set.clip_right(25),
+            BoundsSet::new(vec![Bounds(0, 10), Bounds(20, 25)])
+        );
+        assert_eq!(set.clip_right(60), set);
+        assert_eq!(set.clip_right(0), BoundsSet::new(vec![]));
+    }
+
+    #[test]
+    fn test_intersect() {
+        let set = BoundsSet::new(vec![Bounds(0, 10), Bounds(20, 30), Bounds(40, 50)]);
+
+        assert_eq!(
+            set.intersect(&BoundsSet::new(vec![Bounds(5, 25), Bounds(45, 60)])),
+            BoundsSet::new(vec![Bounds(5, 10), Bounds(20, 25), Bounds(45, 50)])
+        );
+        assert_eq!(
+            set.intersect(&BoundsSet::new(vec![Bounds(11, 19)])),
+            BoundsSet::new(vec![])
+        );
+    }
+
     #[test]
     fn test_sort() {
         assert_eq!(
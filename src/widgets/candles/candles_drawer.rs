@@ -1,27 +1,93 @@
+use std::sync::{Arc, Mutex};
+
 use chrono::{DateTime, Utc};
 use crossbeam::channel::{unbounded, Sender};
 use egui::{
-    plot::{BoxElem, BoxPlot, BoxSpread, Plot},
-    Color32, Stroke,
+    plot::{
+        Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Line, LineStyle, LinkedAxisGroup,
+        MarkerShape, Plot, PlotPoint, PlotPoints, PlotUi, Points, Polygon, Text,
+    },
+    Align2, Color32, Stroke,
 };
 use tracing::{error, info};
 
-use crate::{netstrat::Drawer, sources::binance::Kline, widgets::AppWidget};
+use crate::{
+    netstrat::Drawer,
+    sources::binance::{FundingRate, Interval, Kline, OpenInterest},
+    widgets::AppWidget,
+};
 
-use super::{bounds::Bounds, data::Data};
+use super::{
+    annotations::{self, Annotation, Annotations, Tool},
+    bounds::Bounds,
+    chart_type::ChartType,
+    data::Data,
+    indicators::{Indicator, Series},
+    precision::Precision,
+    resample::resample,
+};
 
 const BOUNDS_SEND_DELAY_MILLIS: i64 = 300;
+const FUNDING_NAME: &str = "funding";
+/// Part of the drawer height taken by the volume histogram.
+const VOLUME_HEIGHT_FRACTION: f32 = 0.25;
+const TAKER_BUY_COLOR: Color32 = Color32::LIGHT_BLUE;
+/// Part of the drawer height taken by every oscillator panel.
+const OSCILLATOR_HEIGHT_FRACTION: f32 = 0.2;
+/// Candles keep at least this part of the drawer height however many panels are shown.
+const MIN_CANDLES_HEIGHT_FRACTION: f32 = 0.4;
+const RSI_LEVELS: [f64; 2] = [30.0, 70.0];
+const INDICATOR_COLORS: [Color32; 6] = [
+    Color32::GOLD,
+    Color32::LIGHT_BLUE,
+    Color32::from_rgb(200, 120, 255),
+    Color32::from_rgb(255, 150, 80),
+    Color32::WHITE,
+    Color32::from_rgb(80, 220, 200),
+];
+const CLOSE_LINE_COLOR: Color32 = Color32::from_rgb(120, 170, 255);
+const OPEN_INTEREST_COLOR: Color32 = Color32::from_rgb(255, 150, 80);
+const ANNOTATION_COLOR: Color32 = Color32::from_rgb(255, 200, 60);
 
 #[derive(Clone)]
 pub struct CandlesDrawer {
     data: Data,
+    /// Candles of drawn klines, one per kline, so indicators can be placed by them.
     val: Vec<BoxElem>,
+    chart_type: ChartType,
+    /// Boxes drawn for the chart type, empty for line charts.
+    shown: Vec<BoxElem>,
+    /// Klines drawn as shown boxes with their real open and close for tooltips.
+    shown_klines: Vec<Kline>,
+    /// Close prices drawn by line charts.
+    closes: Vec<[f64; 2]>,
     bounds_pub: Sender<Bounds>,
     incremental_drag_diff: f32,
     last_time_drag_happened: DateTime<Utc>,
     drag_happened: bool,
     bounds: Bounds,
     enabled: bool,
+    precision: Precision,
+    /// Interval klines are resampled to before drawing. None shows klines as downloaded.
+    display_interval: Option<Interval>,
+    funding: Vec<FundingRate>,
+    /// Funding markers placed under the lows of klines funding happened in.
+    funding_points: Vec<(FundingRate, [f64; 2])>,
+    /// Open interest value in quote asset by time, shown in its own panel when not empty.
+    open_interest: Vec<[f64; 2]>,
+    volume: Vec<Bar>,
+    taker_buy: Vec<Bar>,
+    /// Max volume of drawn klines which may be resampled.
+    max_vol: f64,
+    show_volume: bool,
+    show_taker_buy: bool,
+    indicators: Vec<Series>,
+    /// Open time of the earliest kline changed since indicators were updated.
+    changed_from: i64,
+    /// Keeps x axis of volume and oscillator panels in sync with candles.
+    axes: LinkedAxisGroup,
+    /// Shared with props and other clones so drawing is kept when drawer is replaced.
+    annotations: Arc<Mutex<Annotations>>,
 }
 
 impl Default for CandlesDrawer {
@@ -31,12 +97,30 @@ impl Default for CandlesDrawer {
         Self {
             data: Data::new_candle(),
             val: Default::default(),
+            chart_type: Default::default(),
+            shown: Default::default(),
+            shown_klines: Default::default(),
+            closes: Default::default(),
             bounds_pub: s_bounds,
             last_time_drag_happened: Utc::now(),
             drag_happened: Default::default(),
             bounds: Bounds(0, 0),
             incremental_drag_diff: 0.0,
             enabled: true,
+            precision: Default::default(),
+            display_interval: Default::default(),
+            funding: Default::default(),
+            funding_points: Default::default(),
+            open_interest: Default::default(),
+            volume: Default::default(),
+            taker_buy: Default::default(),
+            max_vol: Default::default(),
+            show_volume: true,
+            show_taker_buy: Default::default(),
+            indicators: Default::default(),
+            changed_from: i64::MIN,
+            axes: LinkedAxisGroup::x(),
+            annotations: Default::default(),
         }
     }
 }
@@ -66,51 +150,486 @@ impl CandlesDrawer {
         res
     }
 
-    pub fn add_data(&mut self, vals: &mut Vec<Kline>) {
-        self.data.append(vals);
-        self.val = self
-            .data
-            .vals
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// Adds klines returning number of replaced klines with the same open time.
+    pub fn add_data(&mut self, vals: &mut Vec<Kline>) -> usize {
+        if let Some(t_open) = vals.iter().map(|k| k.t_open).min() {
+            self.changed_from = self.changed_from.min(t_open);
+        }
+
+        let replaced = self.data.append(vals);
+        self.update_boxes();
+
+        replaced
+    }
+
+    /// Sets funding events of a perpetual contract drawn as markers.
+    pub fn set_funding(&mut self, funding: Vec<FundingRate>) {
+        self.funding = funding;
+        self.update_funding_points();
+    }
+
+    /// Sets open interest statistics of a futures contract drawn under candles.
+    pub fn set_open_interest(&mut self, open_interest: &[OpenInterest]) {
+        self.open_interest = open_interest
+            .iter()
+            .map(|oi| [oi.time as f64, oi.open_interest_value])
+            .collect();
+    }
+
+    pub fn set_display_interval(&mut self, interval: Option<Interval>) {
+        info!("setting display interval: {interval:?}");
+
+        self.display_interval = interval;
+        self.changed_from = i64::MIN;
+        self.update_boxes();
+    }
+
+    pub fn set_chart_type(&mut self, chart_type: ChartType) {
+        info!("setting chart type: {chart_type:?}");
+
+        self.chart_type = chart_type;
+        self.update_boxes();
+    }
+
+    /// Shows volume histogram under candles.
+    pub fn set_show_volume(&mut self, show: bool) {
+        self.show_volume = show;
+    }
+
+    /// Overlays taker buy volume on the volume histogram.
+    pub fn set_show_taker_buy(&mut self, show: bool) {
+        self.show_taker_buy = show;
+    }
+
+    /// Sets indicators keeping already computed series of unchanged ones.
+    pub fn set_indicators(&mut self, indicators: Vec<Indicator>) {
+        info!("setting indicators: {indicators:?}");
+
+        let mut computed = std::mem::take(&mut self.indicators);
+        self.indicators = indicators
+            .into_iter()
+            .map(
+                |indicator| match computed.iter().position(|s| s.indicator() == indicator) {
+                    Some(i) => computed.remove(i),
+                    None => Series::new(indicator),
+                },
+            )
+            .collect();
+
+        self.update_boxes();
+    }
+
+    pub fn annotations(&self) -> Arc<Mutex<Annotations>> {
+        self.annotations.clone()
+    }
+
+    fn update_boxes(&mut self) {
+        let resampled;
+        let data = match self.display_interval {
+            Some(interval) => {
+                let mut data = Data::new_candle();
+                data.append(&mut resample(&self.data.vals, interval));
+                resampled = data;
+                &resampled
+            }
+            None => &self.data,
+        };
+        let klines = &data.vals;
+
+        self.val = klines.iter().map(Self::kline_box).collect();
+        self.shown_klines = match self.chart_type.is_line() {
+            true => vec![],
+            false => self.chart_type.boxes(klines),
+        };
+        self.shown = match self.chart_type {
+            ChartType::Candles => self.val.clone(),
+            _ => self.shown_klines.iter().map(Self::kline_box).collect(),
+        };
+        self.closes = match self.chart_type.is_line() {
+            true => klines
+                .iter()
+                .map(|k| [(k.t_open + k.t_close) as f64 / 2.0, k.close])
+                .collect(),
+            false => vec![],
+        };
+
+        self.volume = klines
             .iter()
-            .map(|k| -> BoxElem {
-                BoxElem::new(
+            .map(|k| {
+                Bar::new((k.t_open + k.t_close) as f64 / 2.0, k.volume)
+                    .name(Data::format_ts(k.t_close as f64))
+                    .stroke(Stroke::new(1.0, Data::k_color(k)))
+                    .fill(Data::k_color(k))
+                    .width((k.t_close - k.t_open) as f64 * 0.9)
+            })
+            .collect();
+
+        self.taker_buy = klines
+            .iter()
+            .map(|k| {
+                let share = match k.volume > 0.0 {
+                    true => k.taker_buy_base_asset_volume / k.volume * 100.0,
+                    false => 0.0,
+                };
+
+                Bar::new(
                     (k.t_open + k.t_close) as f64 / 2.0,
-                    BoxSpread::new(
-                        k.low as f64,
-                        {
-                            match k.open > k.close {
-                                true => k.close as f64,
-                                false => k.open as f64,
-                            }
-                        },
-                        k.open as f64, // we don't need to see median for candle
-                        {
-                            match k.open > k.close {
-                                true => k.open as f64,
-                                false => k.close as f64,
-                            }
-                        },
-                        k.high as f64,
-                    ),
+                    k.taker_buy_base_asset_volume,
                 )
-                .name(Data::format_ts(k.t_close as f64))
-                .stroke(Stroke::new(1.0, Data::k_color(k)))
-                .fill(Data::k_color(k))
-                .whisker_width(0.0)
-                .box_width((k.t_open - k.t_close) as f64 * 0.9)
+                .name(format!(
+                    "{share:.1}% of volume\n{}",
+                    Data::format_ts(k.t_close as f64)
+                ))
+                .stroke(Stroke::new(1.0, TAKER_BUY_COLOR))
+                .fill(TAKER_BUY_COLOR)
+                .width((k.t_close - k.t_open) as f64 * 0.45)
             })
             .collect();
+
+        self.max_vol = data.max_vol();
+
+        // klines before the first changed one keep their indicator values
+        let from = klines.partition_point(|k| k.t_close < self.changed_from);
+        self.indicators
+            .iter_mut()
+            .for_each(|s| s.update(klines, from));
+        self.changed_from = i64::MAX;
+
+        self.update_funding_points();
+    }
+
+    fn kline_box(k: &Kline) -> BoxElem {
+        BoxElem::new(
+            (k.t_open + k.t_close) as f64 / 2.0,
+            BoxSpread::new(
+                k.low,
+                {
+                    match k.open > k.close {
+                        true => k.close,
+                        false => k.open,
+                    }
+                },
+                k.open, // we don't need to see median for candle
+                {
+                    match k.open > k.close {
+                        true => k.open,
+                        false => k.close,
+                    }
+                },
+                k.high,
+            ),
+        )
+        .name(Data::format_ts(k.t_close as f64))
+        .stroke(Stroke::new(1.0, Data::k_color(k)))
+        .fill(Data::k_color(k))
+        .whisker_width(0.0)
+        .box_width((k.t_open - k.t_close) as f64 * 0.9)
+    }
+
+    /// Candles annotations snap to. Boxes of other chart types do not hold real prices.
+    fn snap_boxes(&self) -> &[BoxElem] {
+        &self.val
+    }
+
+    fn update_funding_points(&mut self) {
+        let klines = &self.data.vals;
+        self.funding_points = self
+            .funding
+            .iter()
+            .filter_map(|f| {
+                let idx = klines.partition_point(|k| k.t_close < f.funding_time);
+                klines
+                    .get(idx)
+                    .filter(|k| k.t_open <= f.funding_time)
+                    .map(|k| (*f, [f.funding_time as f64, k.low]))
+            })
+            .collect();
+    }
+
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision
     }
 
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled
     }
 
+    /// Requests bounds update after the plot is dragged far enough.
+    fn track_drag(&mut self, drag_diff: f32) {
+        if drag_diff.abs() > 0.0 {
+            self.incremental_drag_diff += drag_diff;
+
+            // TODO: use step to count min drag diff
+            if self.incremental_drag_diff > (60 * 1000 * 5) as f32 {
+                self.drag_happened = true;
+                self.last_time_drag_happened = Utc::now();
+                self.incremental_drag_diff = 0.0;
+            }
+        }
+    }
+
+    /// Plot points of the indicator line skipping klines the indicator is not ready for.
+    fn indicator_line(&self, series: &Series, line: usize) -> PlotPoints {
+        self.val
+            .iter()
+            .zip(series.points())
+            .filter_map(|(el, p)| p.lines[line].map(|v| [el.argument, v]))
+            .collect()
+    }
+
+    fn indicator_lines(&self, series: &Series, color: Color32) -> Vec<Line> {
+        let indicator = series.indicator();
+        indicator
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| **name != "hist")
+            .map(|(i, name)| {
+                let line = Line::new(self.indicator_line(series, i))
+                    .name(match name.is_empty() {
+                        true => indicator.label(),
+                        false => format!("{} {name}", indicator.label()),
+                    })
+                    .color(color);
+
+                match (indicator, i) {
+                    (Indicator::Bollinger { .. }, 1..) => line.style(LineStyle::dashed_loose()),
+                    (Indicator::Macd { .. }, 1) => line.color(Color32::LIGHT_RED),
+                    _ => line,
+                }
+            })
+            .collect()
+    }
+
+    /// Draws annotations and applies the drawing tool to clicks on the plot.
+    fn show_annotations(&self, plot_ui: &mut PlotUi) {
+        let mut annotations = match self.annotations.lock() {
+            Ok(annotations) => annotations,
+            Err(err) => {
+                error!("failed to lock annotations: {err}");
+                return;
+            }
+        };
+
+        let pointer = plot_ui
+            .pointer_coordinate()
+            .map(|p| match annotations.snap() {
+                true => annotations::snap([p.x, p.y], self.snap_boxes()),
+                false => [p.x, p.y],
+            });
+
+        annotations.items().iter().for_each(|a| match a {
+            Annotation::TrendLine { from, to } => plot_ui.line(
+                Line::new(vec![*from, *to])
+                    .color(ANNOTATION_COLOR)
+                    .width(1.5),
+            ),
+            Annotation::Level { price } => {
+                plot_ui.hline(HLine::new(*price).name("level").color(ANNOTATION_COLOR))
+            }
+            Annotation::Range { from, to } => plot_ui.polygon(
+                Polygon::new(rect(*from, *to))
+                    .name("range")
+                    .color(ANNOTATION_COLOR)
+                    .fill_alpha(0.1),
+            ),
+            Annotation::Note { at, text } => {
+                plot_ui.points(
+                    Points::new(vec![*at])
+                        .shape(MarkerShape::Circle)
+                        .radius(3.0)
+                        .filled(true)
+                        .color(ANNOTATION_COLOR),
+                );
+                plot_ui.text(
+                    Text::new(PlotPoint::new(at[0], at[1]), text.as_str())
+                        .color(ANNOTATION_COLOR)
+                        .anchor(Align2::LEFT_BOTTOM),
+                );
+            }
+        });
+
+        // preview of the annotation until its second point is clicked
+        if let (Some(from), Some(to)) = (annotations.pending(), pointer) {
+            match annotations.tool() {
+                Tool::Range => plot_ui.polygon(
+                    Polygon::new(rect(from, to))
+                        .color(ANNOTATION_COLOR)
+                        .fill_alpha(0.05)
+                        .style(LineStyle::dashed_loose()),
+                ),
+                _ => plot_ui.line(
+                    Line::new(vec![from, to])
+                        .color(ANNOTATION_COLOR)
+                        .style(LineStyle::dashed_loose()),
+                ),
+            }
+        }
+
+        if let Some(p) = pointer.filter(|_| plot_ui.plot_clicked()) {
+            let to_screen = |p: [f64; 2]| {
+                let pos = plot_ui.screen_from_plot(PlotPoint::new(p[0], p[1]));
+                [pos.x as f64, pos.y as f64]
+            };
+            annotations.click(p, to_screen);
+        }
+    }
+
+    fn show_oscillator_plot(&mut self, ui: &mut egui::Ui, idx: usize, height: f32, x_axis: bool) {
+        let series = &self.indicators[idx];
+        let indicator = series.indicator();
+        let precision = self.precision;
+        let lines = self.indicator_lines(series, INDICATOR_COLORS[idx % INDICATOR_COLORS.len()]);
+        let hist = match indicator {
+            Indicator::Macd { .. } => Some(
+                BarChart::new(
+                    self.val
+                        .iter()
+                        .zip(series.points())
+                        .filter_map(|(el, p)| {
+                            p.lines[2].map(|v| {
+                                Bar::new(el.argument, v).width(el.box_width.abs()).fill(
+                                    match v >= 0.0 {
+                                        true => Color32::LIGHT_GREEN,
+                                        false => Color32::LIGHT_RED,
+                                    },
+                                )
+                            })
+                        })
+                        .collect(),
+                )
+                .name(format!("{} hist", indicator.label()))
+                .element_formatter(Box::new(move |bar, _| -> String {
+                    format!(
+                        "hist: {}\n{}",
+                        indicator.format(bar.value, &precision),
+                        Data::format_ts(bar.argument)
+                    )
+                })),
+            ),
+            _ => None,
+        };
+
+        let mut plot = Plot::new(format!("oscillator_{idx}"))
+            .link_axis(self.axes.clone())
+            .height(height)
+            .show_axes([x_axis, true])
+            .label_formatter(move |name, v| -> String {
+                match name.is_empty() {
+                    true => Data::format_ts(v.x),
+                    false => format!(
+                        "{name}: {}\n{}",
+                        indicator.format(v.y, &precision),
+                        Data::format_ts(v.x)
+                    ),
+                }
+            })
+            .x_axis_formatter(|v, _range| Data::format_ts(v));
+        if let Indicator::Rsi { .. } = indicator {
+            plot = plot.include_y(0.0).include_y(100.0);
+        }
+
+        plot.show(ui, |plot_ui| {
+            if let Indicator::Rsi { .. } = indicator {
+                RSI_LEVELS.iter().for_each(|level| {
+                    plot_ui.hline(
+                        HLine::new(*level)
+                            .color(Color32::GRAY)
+                            .style(LineStyle::dashed_dense()),
+                    );
+                });
+            }
+
+            if let Some(hist) = hist {
+                plot_ui.bar_chart(hist);
+            }
+            lines.into_iter().for_each(|line| plot_ui.line(line));
+
+            self.track_drag(plot_ui.pointer_coordinate_drag_delta().x);
+        });
+    }
+
+    fn show_volume_plot(&mut self, ui: &mut egui::Ui, height: f32, x_axis: bool) {
+        let precision = self.precision;
+        let volume = BarChart::new(self.volume.clone())
+            .name("volume")
+            .element_formatter(Box::new(move |bar, _| -> String {
+                format!("volume: {}\n{}", precision.qty(bar.value), bar.name)
+            }));
+        let taker_buy = match self.show_taker_buy {
+            true => Some(
+                BarChart::new(self.taker_buy.clone())
+                    .name("taker buy")
+                    .element_formatter(Box::new(move |bar, _| -> String {
+                        format!("taker buy: {}\n{}", precision.qty(bar.value), bar.name)
+                    })),
+            ),
+            false => None,
+        };
+
+        Plot::new("volume")
+            .link_axis(self.axes.clone())
+            .height(height)
+            .show_axes([x_axis, true])
+            .include_y(0.0)
+            .include_y(self.max_vol)
+            .label_formatter(|_, v| Data::format_ts(v.x))
+            .x_axis_formatter(|v, _range| Data::format_ts(v))
+            .y_axis_formatter(move |v, _range| precision.qty(v))
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(volume);
+                if let Some(taker_buy) = taker_buy {
+                    plot_ui.bar_chart(taker_buy);
+                }
+
+                self.track_drag(plot_ui.pointer_coordinate_drag_delta().x);
+            });
+    }
+
+    fn show_open_interest_plot(&mut self, ui: &mut egui::Ui, height: f32, x_axis: bool) {
+        let precision = self.precision;
+
+        Plot::new("open_interest")
+            .link_axis(self.axes.clone())
+            .height(height)
+            .show_axes([x_axis, true])
+            .label_formatter(move |name, v| -> String {
+                match name.is_empty() {
+                    true => Data::format_ts(v.x),
+                    false => format!("{name}: {}\n{}", precision.qty(v.y), Data::format_ts(v.x)),
+                }
+            })
+            .x_axis_formatter(|v, _range| Data::format_ts(v))
+            .y_axis_formatter(move |v, _range| precision.qty(v))
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(self.open_interest.clone())
+                        .name("open interest")
+                        .color(OPEN_INTEREST_COLOR),
+                );
+
+                self.track_drag(plot_ui.pointer_coordinate_drag_delta().x);
+            });
+    }
+
     pub fn clear(&mut self) {
         self.data = Data::new_candle();
+        self.funding.clear();
+        self.funding_points.clear();
+        self.open_interest.clear();
+        self.changed_from = i64::MIN;
     }
 }
 
+/// Corners of the rectangle with the given opposite corners.
+fn rect(from: [f64; 2], to: [f64; 2]) -> Vec<[f64; 2]> {
+    vec![from, [to[0], from[1]], to, [from[0], to[1]]]
+}
+
 impl AppWidget for CandlesDrawer {
     fn show(&mut self, ui: &mut egui::Ui) {
         if self.drag_happened
@@ -128,53 +647,158 @@ impl AppWidget for CandlesDrawer {
 
             self.drag_happened = false;
         }
+        let precision = self.precision;
+        let funding_points = self.funding_points.clone();
+        let show_volume = self.show_volume;
+        let show_open_interest = !self.open_interest.is_empty();
+        let chart_type = self.chart_type;
+        let drawing = match self.annotations.lock() {
+            Ok(annotations) => annotations.tool() != Tool::Pan,
+            Err(_) => false,
+        };
+        let oscillators = (0..self.indicators.len())
+            .filter(|i| !self.indicators[*i].indicator().overlay())
+            .collect::<Vec<_>>();
+        let overlays = self
+            .indicators
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.indicator().overlay())
+            .flat_map(|(i, s)| {
+                self.indicator_lines(s, INDICATOR_COLORS[i % INDICATOR_COLORS.len()])
+            })
+            .collect::<Vec<_>>();
+
+        let total_height = ui.available_height();
+        let volume_height = total_height * VOLUME_HEIGHT_FRACTION;
+        let oscillator_height = total_height * OSCILLATOR_HEIGHT_FRACTION;
+        let candles_height = (total_height
+            - show_volume as usize as f32 * volume_height
+            - show_open_interest as usize as f32 * oscillator_height
+            - oscillators.len() as f32 * oscillator_height)
+            .max(total_height * MIN_CANDLES_HEIGHT_FRACTION);
         ui.add_enabled_ui(self.enabled, |ui| {
             Plot::new("candles")
-                .label_formatter(|_, v| -> String { Data::format_ts(v.x) })
+                .link_axis(self.axes.clone())
+                .height(candles_height)
+                // clicks place annotations while a drawing tool is selected
+                .allow_drag(!drawing)
+                // time is shown under the bottom panel
+                .show_axes([
+                    !show_volume && !show_open_interest && oscillators.is_empty(),
+                    true,
+                ])
+                .label_formatter(move |name, v| -> String {
+                    if name.is_empty() {
+                        return Data::format_ts(v.x);
+                    }
+
+                    if name != FUNDING_NAME {
+                        return format!(
+                            "{name}: {}\n{}",
+                            precision.price(v.y),
+                            Data::format_ts(v.x)
+                        );
+                    }
+
+                    match funding_points
+                        .iter()
+                        .min_by_key(|(f, _)| (f.funding_time - v.x as i64).abs())
+                    {
+                        Some((f, _)) => format!(
+                            "funding rate: {:.4}%\n{}",
+                            f.funding_rate * 100.0,
+                            Data::format_ts(f.funding_time as f64)
+                        ),
+                        None => Data::format_ts(v.x),
+                    }
+                })
                 .x_axis_formatter(|v, _range| Data::format_ts(v))
                 .show(ui, |plot_ui| {
-                    plot_ui.box_plot(
-                        BoxPlot::new(self.val.clone())
-                            .element_formatter(Box::new(|el, _| -> String {
-                                format!(
-                                    "open: {:.8}\nclose: {:.8}\nhigh: {:.8}\nlow: {:.8}\n{}",
-                                    {
-                                        match el.fill == Color32::LIGHT_RED {
-                                            true => el.spread.quartile3,
-                                            false => el.spread.quartile1,
-                                        }
-                                    },
-                                    {
-                                        match el.fill == Color32::LIGHT_RED {
-                                            true => el.spread.quartile1,
-                                            false => el.spread.quartile3,
+                    match chart_type {
+                        ChartType::Line | ChartType::Area => {
+                            let line = Line::new(self.closes.clone())
+                                .name("close")
+                                .color(CLOSE_LINE_COLOR);
+                            plot_ui.line(match chart_type {
+                                ChartType::Area => line.fill(
+                                    self.closes
+                                        .iter()
+                                        .map(|p| p[1])
+                                        .fold(f64::INFINITY, f64::min)
+                                        as f32,
+                                ),
+                                _ => line,
+                            });
+                        }
+                        _ => {
+                            let shown_klines = self.shown_klines.clone();
+                            plot_ui.box_plot(
+                                BoxPlot::new(self.shown.clone())
+                                    .element_formatter(Box::new(move |el, _| -> String {
+                                        // boxes are placed at the middle of their klines
+                                        let idx = shown_klines.partition_point(|k| {
+                                            ((k.t_open + k.t_close) as f64 / 2.0) < el.argument
+                                        });
+                                        match shown_klines.get(idx) {
+                                            Some(k) => chart_type.format_box(k, &precision),
+                                            None => Data::format_ts(el.argument),
                                         }
-                                    },
-                                    el.spread.upper_whisker,
-                                    el.spread.lower_whisker,
-                                    Data::format_ts(el.argument),
-                                )
-                            }))
-                            .vertical(),
-                    );
+                                    }))
+                                    .vertical(),
+                            )
+                        }
+                    }
 
-                    let plot_bounds = plot_ui.plot_bounds();
-                    self.bounds = Bounds(plot_bounds.min()[0] as i64, plot_bounds.max()[0] as i64);
+                    overlays.into_iter().for_each(|line| plot_ui.line(line));
 
-                    let drag_diff = plot_ui.pointer_coordinate_drag_delta().x;
-                    if drag_diff.abs() > 0.0 {
-                        self.incremental_drag_diff += drag_diff;
+                    self.show_annotations(plot_ui);
 
-                        // TODO: use step to count min drag diff
-                        if self.incremental_drag_diff > (60 * 1000 * 5) as f32 {
-                            self.drag_happened = true;
-                            self.last_time_drag_happened = Utc::now();
-                            self.incremental_drag_diff = 0.0;
-                        }
+                    if !self.funding_points.is_empty() {
+                        let (positive, negative): (Vec<_>, Vec<_>) = self
+                            .funding_points
+                            .iter()
+                            .partition(|(f, _)| f.funding_rate >= 0.0);
+                        [
+                            (positive, Color32::LIGHT_GREEN),
+                            (negative, Color32::LIGHT_RED),
+                        ]
+                        .into_iter()
+                        .for_each(|(points, color)| {
+                            plot_ui.points(
+                                Points::new(points.iter().map(|(_, p)| *p).collect::<Vec<_>>())
+                                    .name(FUNDING_NAME)
+                                    .shape(MarkerShape::Up)
+                                    .radius(4.0)
+                                    .filled(true)
+                                    .color(color),
+                            );
+                        });
                     }
 
+                    let plot_bounds = plot_ui.plot_bounds();
+                    self.bounds = Bounds(plot_bounds.min()[0] as i64, plot_bounds.max()[0] as i64);
+
+                    self.track_drag(plot_ui.pointer_coordinate_drag_delta().x);
+
                     plot_ui.ctx().request_repaint();
-                })
+                });
+
+            if show_volume {
+                self.show_volume_plot(
+                    ui,
+                    volume_height,
+                    !show_open_interest && oscillators.is_empty(),
+                );
+            }
+
+            if show_open_interest {
+                self.show_open_interest_plot(ui, oscillator_height, oscillators.is_empty());
+            }
+
+            oscillators.iter().enumerate().for_each(|(n, idx)| {
+                self.show_oscillator_plot(ui, *idx, oscillator_height, n + 1 == oscillators.len());
+            });
         });
     }
 }
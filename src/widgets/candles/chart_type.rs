@@ -0,0 +1,314 @@
+use egui::{ComboBox, DragValue, Ui};
+
+use crate::sources::binance::Kline;
+
+use super::{data::Data, precision::Precision};
+
+/// Part of the last close price the default renko brick is.
+const DEFAULT_BRICK_FRACTION: f64 = 0.01;
+
+/// How klines are drawn on the candles plot.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChartType {
+    #[default]
+    Candles,
+    /// Candles of averaged prices smoothing out the noise.
+    HeikinAshi,
+    /// Close prices joined with a line.
+    Line,
+    /// Close price line filled down to the lowest close.
+    Area,
+    /// Bricks of fixed price size added when close moves by a whole brick.
+    Renko { brick: f64 },
+}
+
+impl ChartType {
+    /// Renko brick here is a placeholder picked from prices when the type is selected.
+    pub const ALL: [ChartType; 5] = [
+        ChartType::Candles,
+        ChartType::HeikinAshi,
+        ChartType::Line,
+        ChartType::Area,
+        ChartType::Renko { brick: 0.0 },
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            ChartType::Candles => "candles",
+            ChartType::HeikinAshi => "heikin-ashi",
+            ChartType::Line => "line",
+            ChartType::Area => "area",
+            ChartType::Renko { .. } => "renko",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            ChartType::Candles => "Open, high, low and close of every kline",
+            ChartType::HeikinAshi => {
+                "Candles of averaged prices: close is the mean of ohlc, open is the middle of the previous candle body"
+            }
+            ChartType::Line => "Close prices joined with a line",
+            ChartType::Area => "Close price line with the area under it filled",
+            ChartType::Renko { .. } => {
+                "Bricks of fixed size drawn when close moves a whole brick from the last one, reversal takes two bricks"
+            }
+        }
+    }
+
+    /// Line and area charts draw close prices instead of boxes.
+    pub fn is_line(&self) -> bool {
+        matches!(self, ChartType::Line | ChartType::Area)
+    }
+
+    /// Klines drawn as boxes, e.g. heikin-ashi candles or renko bricks.
+    pub fn boxes(&self, klines: &[Kline]) -> Vec<Kline> {
+        match self {
+            ChartType::HeikinAshi => heikin_ashi(klines),
+            ChartType::Renko { brick } => renko(klines, *brick),
+            _ => klines.to_vec(),
+        }
+    }
+
+    /// Picks renko brick from prices if it is not picked yet returning true if it was picked.
+    pub fn pick_brick(&mut self, klines: &[Kline]) -> bool {
+        match self {
+            ChartType::Renko { brick } if *brick <= 0.0 && !klines.is_empty() => {
+                *brick = default_brick(klines);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drops renko brick so it is picked again, e.g. from prices of another symbol.
+    pub fn reset_brick(&mut self) {
+        if let ChartType::Renko { brick } = self {
+            *brick = 0.0;
+        }
+    }
+
+    /// Tooltip of the kline drawn as a box by this chart type.
+    pub fn format_box(&self, k: &Kline, precision: &Precision) -> String {
+        let ts = Data::format_ts((k.t_open + k.t_close) as f64 / 2.0);
+
+        match self {
+            ChartType::Renko { .. } => format!(
+                "brick: {} → {}\n{ts}",
+                precision.price(k.open),
+                precision.price(k.close),
+            ),
+            _ => format!(
+                "{prefix}open: {}\n{prefix}close: {}\nhigh: {}\nlow: {}\n{ts}",
+                precision.price(k.open),
+                precision.price(k.close),
+                precision.price(k.high),
+                precision.price(k.low),
+                prefix = match self {
+                    ChartType::HeikinAshi => "ha ",
+                    _ => "",
+                },
+            ),
+        }
+    }
+
+    /// Shows chart type selector and renko brick editor returning true if anything changed.
+    pub fn settings_ui(&mut self, ui: &mut Ui, klines: &[Kline]) -> bool {
+        let mut changed = false;
+        ComboBox::from_id_source("graph_chart_type")
+            .selected_text(self.name())
+            .show_ui(ui, |ui| {
+                ChartType::ALL.iter().for_each(|t| {
+                    if ui
+                        .selectable_label(t.name() == self.name(), t.name())
+                        .on_hover_text(t.hint())
+                        .clicked()
+                        && t.name() != self.name()
+                    {
+                        *self = match t {
+                            ChartType::Renko { .. } => ChartType::Renko {
+                                brick: default_brick(klines),
+                            },
+                            t => *t,
+                        };
+                        changed = true;
+                    }
+                });
+            })
+            .response
+            .on_hover_text(self.hint());
+
+        if let ChartType::Renko { brick } = self {
+            // too small bricks would turn every kline into thousands of them
+            let min_brick = default_brick(klines) * DEFAULT_BRICK_FRACTION;
+            let speed = *brick * DEFAULT_BRICK_FRACTION;
+            changed |= ui
+                .add(
+                    DragValue::new(brick)
+                        .clamp_range(min_brick..=f64::MAX)
+                        .speed(speed)
+                        .prefix("brick: "),
+                )
+                .on_hover_text("Renko brick size in quote asset")
+                .changed();
+        }
+
+        changed
+    }
+}
+
+/// Heikin-ashi candles keeping times and volumes of klines.
+pub fn heikin_ashi(klines: &[Kline]) -> Vec<Kline> {
+    klines.iter().fold(
+        Vec::with_capacity(klines.len()),
+        |mut acc: Vec<Kline>, k| {
+            let close = (k.open + k.high + k.low + k.close) / 4.0;
+            let open = match acc.last() {
+                Some(prev) => (prev.open + prev.close) / 2.0,
+                None => (k.open + k.close) / 2.0,
+            };
+
+            acc.push(Kline {
+                open,
+                close,
+                high: k.high.max(open).max(close),
+                low: k.low.min(open).min(close),
+                ..*k
+            });
+
+            acc
+        },
+    )
+}
+
+/// Renko bricks built from close prices starting at the first close.
+///
+/// Bricks keep the time of the kline they were formed in so they share time axis with other
+/// panels. Several bricks formed in one kline split its time evenly.
+pub fn renko(klines: &[Kline], brick: f64) -> Vec<Kline> {
+    let base = match klines.first() {
+        Some(k) if brick > 0.0 => k.close,
+        _ => return vec![],
+    };
+
+    klines.iter().fold(Vec::new(), |mut acc: Vec<Kline>, k| {
+        let (top, bottom) = match acc.last() {
+            Some(last) => (last.open.max(last.close), last.open.min(last.close)),
+            None => (base, base),
+        };
+
+        let up = ((k.close - top) / brick).floor().max(0.0) as usize;
+        let down = ((bottom - k.close) / brick).floor().max(0.0) as usize;
+        let moves = (0..up)
+            .map(|i| top + brick * i as f64)
+            .map(|open| (open, open + brick))
+            .chain(
+                (0..down)
+                    .map(|i| bottom - brick * i as f64)
+                    .map(|open| (open, open - brick)),
+            )
+            .collect::<Vec<_>>();
+
+        let span = (k.t_close - k.t_open + 1) / moves.len().max(1) as i64;
+        moves
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, (open, close))| {
+                let t_open = k.t_open + i as i64 * span;
+                acc.push(Kline {
+                    t_open,
+                    t_close: t_open + span - 1,
+                    open,
+                    close,
+                    high: open.max(close),
+                    low: open.min(close),
+                    ..Default::default()
+                });
+            });
+
+        acc
+    })
+}
+
+/// Brick of about a percent of the last close rounded to one significant digit.
+fn default_brick(klines: &[Kline]) -> f64 {
+    let v = match klines.last() {
+        Some(k) if k.close > 0.0 => k.close * DEFAULT_BRICK_FRACTION,
+        _ => return 1.0,
+    };
+    let magnitude = 10f64.powf(v.log10().floor());
+
+    (v / magnitude).round() * magnitude
+}
+
+#[cfg(test)]
+mod chart_type_tests {
+    use crate::widgets::candles::test_klines::minute;
+
+    use super::*;
+
+    #[test]
+    fn test_heikin_ashi() {
+        let klines = vec![
+            minute(0, 10.0, 14.0, 8.0, 12.0),
+            minute(1, 12.0, 13.0, 9.0, 10.0),
+        ];
+
+        let ha = heikin_ashi(&klines);
+
+        assert_eq!((ha[0].open, ha[0].close), (11.0, 11.0));
+        assert_eq!((ha[0].high, ha[0].low), (14.0, 8.0));
+        assert_eq!((ha[1].open, ha[1].close), (11.0, 11.0));
+        assert_eq!(ha[1].t_open, klines[1].t_open);
+    }
+
+    #[test]
+    fn test_renko() {
+        let klines = vec![
+            minute(0, 100.0, 100.0, 100.0, 100.0),
+            minute(1, 100.0, 125.0, 100.0, 125.0),
+            minute(2, 125.0, 125.0, 105.0, 105.0),
+            minute(3, 105.0, 105.0, 95.0, 95.0),
+        ];
+
+        let bricks = renko(&klines, 10.0)
+            .iter()
+            .map(|b| (b.t_open, b.open, b.close))
+            .collect::<Vec<_>>();
+
+        // reversal from 120 needs close at or below 100
+        assert_eq!(
+            bricks,
+            vec![
+                (60_000, 100.0, 110.0),
+                (90_000, 110.0, 120.0),
+                (180_000, 110.0, 100.0),
+            ]
+        );
+        assert!(renko(&klines, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_pick_brick() {
+        let mut chart_type = ChartType::Renko { brick: 0.0 };
+        assert!(!chart_type.pick_brick(&[]));
+
+        assert!(chart_type.pick_brick(&[minute(0, 1.0, 1.0, 1.0, 23_456.0)]));
+        assert_eq!(chart_type, ChartType::Renko { brick: 200.0 });
+        assert!(!chart_type.pick_brick(&[minute(0, 1.0, 1.0, 1.0, 2.0)]));
+
+        chart_type.reset_brick();
+        assert!(chart_type.pick_brick(&[minute(0, 1.0, 1.0, 1.0, 2.0)]));
+        assert_eq!(chart_type, ChartType::Renko { brick: 0.02 });
+
+        let mut candles = ChartType::Candles;
+        candles.reset_brick();
+        assert!(!candles.pick_brick(&[minute(0, 1.0, 1.0, 1.0, 2.0)]));
+    }
+
+    #[test]
+    fn test_default_brick() {
+        assert_eq!(default_brick(&[minute(0, 1.0, 1.0, 1.0, 23_456.0)]), 200.0);
+        assert_eq!(default_brick(&[]), 1.0);
+    }
+}
@@ -0,0 +1,136 @@
+use egui::plot::{Legend, Line, Plot};
+
+use crate::{netstrat::Drawer, sources::binance::Kline, widgets::AppWidget};
+
+use super::data::Data;
+
+/// How closes of different symbols are brought to a common base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Percent change from the price at the range start.
+    #[default]
+    PercentChange,
+    /// Price rebased so the range starts at 100.
+    Rebased,
+}
+
+impl Normalization {
+    pub const ALL: [Normalization; 2] = [Normalization::PercentChange, Normalization::Rebased];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Normalization::PercentChange => "percent change",
+            Normalization::Rebased => "rebased to 100",
+        }
+    }
+
+    fn apply(&self, base: f64, v: f64) -> f64 {
+        match self {
+            Normalization::PercentChange => (v / base - 1.0) * 100.0,
+            Normalization::Rebased => v / base * 100.0,
+        }
+    }
+
+    fn unit(&self) -> &str {
+        match self {
+            Normalization::PercentChange => "%",
+            Normalization::Rebased => "",
+        }
+    }
+}
+
+/// Normalizes closes of klines sorted by time using open of the first kline as base.
+pub fn normalize(klines: &[Kline], normalization: Normalization) -> Vec<[f64; 2]> {
+    let base = match klines.first() {
+        Some(k) if k.open != 0.0 => k.open,
+        _ => return vec![],
+    };
+
+    klines
+        .iter()
+        .map(|k| [k.t_close as f64, normalization.apply(base, k.close)])
+        .collect()
+}
+
+/// Draws normalized closes of several symbols in one plot.
+#[derive(Default, Clone)]
+pub struct CompareDrawer {
+    series: Vec<(String, Vec<[f64; 2]>)>,
+    normalization: Normalization,
+}
+
+// TODO:  refactor drawer trait to be more generalized
+/// Compared series are drawn as plot lines, no image is ever produced for them.
+impl Drawer for CompareDrawer {
+    fn update_image(&mut self, _image: egui::ColorImage) {}
+
+    fn has_unread_image(&self) -> bool {
+        false
+    }
+}
+
+impl CompareDrawer {
+    pub fn set_series(
+        &mut self,
+        series: Vec<(String, Vec<[f64; 2]>)>,
+        normalization: Normalization,
+    ) {
+        self.series = series;
+        self.normalization = normalization;
+    }
+}
+
+impl AppWidget for CompareDrawer {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let normalization = self.normalization;
+        Plot::new("compare")
+            .legend(Legend::default())
+            .label_formatter(move |name, v| -> String {
+                format!(
+                    "{name}\n{:.2}{}\n{}",
+                    v.y,
+                    normalization.unit(),
+                    Data::format_ts(v.x)
+                )
+            })
+            .x_axis_formatter(|v, _range| Data::format_ts(v))
+            .show(ui, |plot_ui| {
+                self.series.iter().for_each(|(symbol, vals)| {
+                    plot_ui.line(Line::new(vals.clone()).name(symbol));
+                });
+            });
+    }
+}
+
+#[cfg(test)]
+mod compare_drawer_tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let klines = vec![
+            Kline {
+                t_close: 59,
+                open: 10.0,
+                close: 12.5,
+                ..Default::default()
+            },
+            Kline {
+                t_close: 119,
+                open: 12.5,
+                close: 15.0,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            normalize(&klines, Normalization::PercentChange),
+            vec![[59.0, 25.0], [119.0, 50.0]]
+        );
+        assert_eq!(
+            normalize(&klines, Normalization::Rebased),
+            vec![[59.0, 125.0], [119.0, 150.0]]
+        );
+        assert!(normalize(&[], Normalization::Rebased).is_empty());
+    }
+}
@@ -0,0 +1,428 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use egui::{CentralPanel, ComboBox, ProgressBar, TopBottomPanel, Ui};
+use egui_notify::{Anchor, Toasts};
+use serde::Serialize;
+use tracing::{debug, error, info};
+
+use crate::netstrat::Drawer;
+use crate::sources::binance::{errors::ClientError, Kline, Symbol};
+use crate::sources::{AsyncClient, Download, MarketDataSource, Tagged};
+use crate::widgets::AppWidget;
+
+use super::compare_drawer::{normalize, CompareDrawer, Normalization};
+use super::data::Data;
+use super::export::write_csv;
+use super::loading_state::LoadingState;
+use super::step::Step;
+use super::time_range_settings::TimeRangeSettings;
+use super::TimeRange;
+
+const MAX_CONCURRENT_PAGES: usize = 10;
+
+/// Downloaded klines of a single compared symbol.
+struct Series {
+    symbol: String,
+    data: Data,
+    loading: LoadingState,
+    download: Download,
+    /// Some pages failed to load so the series has gaps.
+    failed: bool,
+}
+
+#[derive(Serialize)]
+struct CompareRow<'a> {
+    symbol: &'a str,
+    t_close: i64,
+    close: f64,
+    normalized: f64,
+}
+
+/// Plots closes of several symbols normalized to a common base.
+///
+/// Every symbol is downloaded with the same time range so all series are split
+/// to the same pages.
+pub struct CompareProps {
+    time_range: Box<dyn AppWidget>,
+
+    drawer: CompareDrawer,
+    series: Vec<Series>,
+    props: TimeRangeSettings,
+    normalization: Normalization,
+
+    max_frame_pages: usize,
+    data_changed: bool,
+    export_triggered: bool,
+
+    toasts: Toasts,
+
+    client: AsyncClient,
+
+    klines_pub: Sender<Tagged<Result<Vec<Kline>, ClientError>>>,
+    klines_sub: Receiver<Tagged<Result<Vec<Kline>, ClientError>>>,
+    drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+    symbol_sub: Receiver<Symbol>,
+    props_sub: Receiver<TimeRangeSettings>,
+    export_sub: Receiver<TimeRangeSettings>,
+}
+
+impl CompareProps {
+    pub fn new(
+        source: Arc<dyn MarketDataSource>,
+        symbol_sub: Receiver<Symbol>,
+        drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+    ) -> Self {
+        info!("initing widget compare");
+
+        let (_, r_symbols) = unbounded();
+        let (s_props, r_props) = unbounded();
+        let (_, r_props1) = unbounded();
+        let (s_export, r_export) = unbounded();
+        let (s_klines, r_klines) = unbounded();
+
+        let props = TimeRangeSettings::default();
+        let time_range = Box::new(TimeRange::new(
+            r_symbols,
+            s_props,
+            r_props1,
+            s_export,
+            props.clone(),
+        ));
+
+        Self {
+            max_frame_pages: 50,
+
+            time_range,
+            props,
+
+            client: AsyncClient::new(source, MAX_CONCURRENT_PAGES),
+
+            toasts: Toasts::default().with_anchor(Anchor::TopRight),
+
+            symbol_sub,
+            drawer_pub,
+            props_sub: r_props,
+            export_sub: r_export,
+            klines_sub: r_klines,
+            klines_pub: s_klines,
+
+            drawer: Default::default(),
+            series: Default::default(),
+            normalization: Default::default(),
+            data_changed: Default::default(),
+            export_triggered: Default::default(),
+        }
+    }
+
+    /// Adds symbol to comparison or removes it if it is already compared.
+    fn toggle_symbol(&mut self, symbol: String) {
+        if let Some(idx) = self.series.iter().position(|s| s.symbol == symbol) {
+            self.remove_symbol(idx);
+            return;
+        }
+
+        info!("adding symbol to comparison: {symbol}");
+
+        let mut series = Series {
+            symbol,
+            data: Data::default(),
+            loading: LoadingState::default(),
+            download: self.client.start_download(),
+            failed: false,
+        };
+        self.start_download(&mut series);
+        self.series.push(series);
+    }
+
+    fn remove_symbol(&mut self, idx: usize) {
+        let series = self.series.remove(idx);
+        info!("removing symbol from comparison: {}", series.symbol);
+
+        series.download.cancel();
+        self.data_changed = true;
+    }
+
+    /// Restarts downloads of all symbols for the new time range.
+    fn apply_props(&mut self, props: TimeRangeSettings) {
+        debug!("applying props to compared symbols: {props:?}");
+
+        self.props = props;
+
+        let mut series = std::mem::take(&mut self.series);
+        series.iter_mut().for_each(|s| self.restart_download(s));
+        self.series = series;
+        self.data_changed = true;
+    }
+
+    /// Downloads again series which have failed pages.
+    fn retry_failed(&mut self) {
+        let mut series = std::mem::take(&mut self.series);
+        series.iter_mut().filter(|s| s.failed).for_each(|s| {
+            info!("retrying download of: {}", s.symbol);
+            self.restart_download(s);
+        });
+        self.series = series;
+        self.data_changed = true;
+    }
+
+    fn restart_download(&self, series: &mut Series) {
+        series.download.cancel();
+        series.download = self.client.start_download();
+        series.data = Data::default();
+        self.start_download(series);
+    }
+
+    fn start_download(&self, series: &mut Series) {
+        series.failed = false;
+
+        let step = Step::from(self.props.interval);
+        let bounds = self.props.loadable_bounds();
+        series.loading = match LoadingState::new(&bounds, step, self.props.limit) {
+            Some(loading) => loading,
+            None => {
+                error!("failed to initialize loading state");
+                return;
+            }
+        };
+
+        debug!(
+            "{} splitted in {} pages; starting download...",
+            series.symbol,
+            series.loading.pages()
+        );
+
+        while series.loading.get_next_page().is_some() {
+            self.client.kline(
+                &series.download,
+                series.symbol.clone(),
+                self.props.interval,
+                series.loading.left_edge(),
+                series.loading.page_size(),
+                self.klines_pub.clone(),
+            );
+        }
+    }
+
+    fn progress(&mut self) -> f32 {
+        if self.series.is_empty() {
+            return 1.0;
+        }
+
+        let total: f32 = self.series.iter_mut().map(|s| s.loading.progress()).sum();
+        total / self.series.len() as f32
+    }
+
+    /// Klines of the series inside the selected time range.
+    fn klines_in_range<'a>(&self, series: &'a Series) -> Vec<&'a Kline> {
+        series
+            .data
+            .vals
+            .iter()
+            .filter(|k| self.props.bounds.contains(k.t_open))
+            .collect()
+    }
+
+    fn normalized(&self) -> Vec<(String, Vec<[f64; 2]>)> {
+        self.series
+            .iter()
+            .map(|s| {
+                let klines: Vec<Kline> = self.klines_in_range(s).into_iter().copied().collect();
+                (s.symbol.clone(), normalize(&klines, self.normalization))
+            })
+            .collect()
+    }
+
+    fn export_data(&mut self) {
+        debug!("exporting comparison");
+
+        let name = format!(
+            "compare_{}_{}_{:?}.csv",
+            self.props.start_time().timestamp(),
+            self.props.end_time().timestamp(),
+            self.props.interval,
+        );
+
+        let normalized = self.normalized();
+        let rows: Vec<CompareRow> = self
+            .series
+            .iter()
+            .zip(normalized.iter())
+            .flat_map(|(s, (_, vals))| {
+                self.klines_in_range(s)
+                    .into_iter()
+                    .zip(vals.iter())
+                    .map(|(k, v)| CompareRow {
+                        symbol: &s.symbol,
+                        t_close: k.t_close,
+                        close: k.close,
+                        normalized: v[1],
+                    })
+            })
+            .collect();
+
+        match write_csv(&name, &rows) {
+            Ok(abs_path) => {
+                self.toasts
+                    .success("File exported")
+                    .set_duration(Some(Duration::from_secs(3)));
+                info!("exported to file: {abs_path:?}");
+            }
+            Err(err) => {
+                error!("failed to export comparison with error: {err}");
+                self.toasts.error("Failed to export comparison");
+            }
+        }
+
+        self.export_triggered = false;
+    }
+
+    fn update(&mut self) {
+        let symbol_wrapped = self.symbol_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(symbol) = symbol_wrapped {
+            debug!("got symbol: {}", symbol.symbol);
+
+            self.toggle_symbol(symbol.symbol);
+        }
+
+        let show_wrapped = self.props_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(props) = show_wrapped {
+            debug!("got show button pressed: {props:?}");
+
+            self.apply_props(props);
+        }
+
+        let export_wrapped = self.export_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(props) = export_wrapped {
+            debug!("got export msg: {props:?}");
+
+            self.export_triggered = true;
+            if props != self.props {
+                self.apply_props(props);
+            }
+        }
+
+        let mut has_error = false;
+        for _ in 0..self.max_frame_pages {
+            let tagged = match self.klines_sub.recv_timeout(Duration::from_millis(1)) {
+                Ok(tagged) => tagged,
+                Err(_) => break,
+            };
+
+            let series = match self
+                .series
+                .iter_mut()
+                .find(|s| s.download.id() == tagged.request_id)
+            {
+                Some(series) => series,
+                None => {
+                    debug!("dropping stale page of download: {}", tagged.request_id);
+                    continue;
+                }
+            };
+
+            series.loading.inc_loaded_pages(1);
+            match tagged.result {
+                Ok(mut klines) => {
+                    series.data.append(&mut klines);
+                    self.data_changed = true;
+                }
+                Err(err) => {
+                    error!("failed to get klines for {}: {err}", series.symbol);
+                    series.failed = true;
+                    has_error = true;
+                }
+            }
+        }
+
+        if has_error {
+            self.toasts.error("Failed to get candles from Binance");
+        }
+
+        if self.progress() == 1.0 && self.export_triggered {
+            match self.series.iter().any(|s| s.failed) {
+                true => {
+                    error!("comparison has failed pages, skipping export");
+                    self.toasts
+                        .error("Some candles failed to load, retry them before exporting");
+                    self.export_triggered = false;
+                }
+                false => self.export_data(),
+            }
+        }
+    }
+
+    fn draw_data(&mut self, ui: &Ui) {
+        if self.data_changed {
+            ui.ctx().request_repaint();
+            self.drawer
+                .set_series(self.normalized(), self.normalization);
+            self.drawer_pub
+                .send(Arc::new(Mutex::new(Box::new(self.drawer.clone()))))
+                .unwrap();
+            self.data_changed = false;
+        }
+    }
+}
+
+impl AppWidget for CompareProps {
+    fn show(&mut self, ui: &mut Ui) {
+        self.update();
+
+        self.draw_data(ui);
+
+        TopBottomPanel::top("compare_toolbar").show_inside(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let mut normalization = self.normalization;
+                ComboBox::from_id_source("compare_normalization")
+                    .selected_text(normalization.name())
+                    .show_ui(ui, |ui| {
+                        Normalization::ALL.iter().for_each(|n| {
+                            ui.selectable_value(&mut normalization, *n, n.name());
+                        });
+                    });
+                if normalization != self.normalization {
+                    info!("setting normalization: {normalization:?}");
+                    self.normalization = normalization;
+                    self.data_changed = true;
+                }
+
+                let mut removed = None;
+                self.series.iter().enumerate().for_each(|(i, s)| {
+                    let button = match s.failed {
+                        true => ui
+                            .button(format!("{} ⚠ ✖", s.symbol))
+                            .on_hover_text("Some candles failed to load"),
+                        false => ui.button(format!("{} ✖", s.symbol)),
+                    };
+                    if button.clicked() {
+                        removed = Some(i);
+                    }
+                });
+                if let Some(idx) = removed {
+                    self.remove_symbol(idx);
+                }
+
+                if self.series.iter().any(|s| s.failed) && ui.button("retry failed").clicked() {
+                    self.retry_failed();
+                }
+
+                let progress = self.progress();
+                if progress < 1.0 {
+                    ui.add(ProgressBar::new(progress).show_percentage().animate(true));
+                }
+            });
+        });
+
+        CentralPanel::default().show_inside(ui, |ui| {
+            if self.series.is_empty() {
+                ui.label("Select symbols to compare");
+            }
+
+            self.time_range.show(ui);
+        });
+
+        self.toasts.show(ui.ctx());
+    }
+}
@@ -23,9 +23,22 @@ impl Data {
         }
     }
 
-    pub fn append(&mut self, vals: &mut Vec<Kline>) {
+    /// Appends klines replacing already present klines with the same open time.
+    ///
+    /// Returns number of replaced klines.
+    pub fn append(&mut self, vals: &mut Vec<Kline>) -> usize {
         self.vals.append(vals);
+        let len = self.vals.len();
+
+        // sort is stable so after reverse the most recent kline goes first and survives dedup
+        self.vals.sort_by_key(|el| el.t_open);
+        self.vals.reverse();
+        self.vals.dedup_by_key(|el| el.t_open);
+        self.vals.reverse();
+
         self.compute_stats();
+
+        len - self.vals.len()
     }
 
     pub fn max_x(&self) -> f64 {
@@ -64,6 +77,10 @@ impl Data {
     }
 
     fn compute_stats(&mut self) {
+        if self.vals.is_empty() {
+            return;
+        }
+
         self.vals.sort_by_key(|el| el.t_close);
 
         self.max_y = self
@@ -77,7 +94,7 @@ impl Data {
                 Ordering::Less
             })
             .unwrap()
-            .high as f64;
+            .high;
 
         self.min_y = self
             .vals
@@ -90,14 +107,73 @@ impl Data {
                 Ordering::Greater
             })
             .unwrap()
-            .low as f64;
+            .low;
+
+        self.max_vol = self.vals.iter().map(|k| k.volume).fold(0.0, f64::max);
 
         self.max_x = self.vals.last().unwrap().t_close as f64;
         self.min_x = self.vals.first().unwrap().t_open as f64;
 
         debug!(
-            "computed data props : max_x: {}, min_x: {}, max_y: {}, min_y: {}",
-            self.max_x, self.min_x, self.max_y, self.min_y,
+            "computed data props : max_x: {}, min_x: {}, max_y: {}, min_y: {}, max_vol: {}",
+            self.max_x, self.min_x, self.max_y, self.min_y, self.max_vol,
         );
     }
 }
+
+#[cfg(test)]
+mod data_tests {
+    use super::*;
+
+    #[test]
+    fn test_append_replaces_same_open_time() {
+        let mut data = Data::new_candle();
+        data.append(&mut vec![
+            Kline {
+                t_open: 0,
+                t_close: 59,
+                close: 1.0,
+                ..Default::default()
+            },
+            Kline {
+                t_open: 60,
+                t_close: 119,
+                close: 2.0,
+                ..Default::default()
+            },
+        ]);
+
+        let replaced = data.append(&mut vec![Kline {
+            t_open: 60,
+            t_close: 119,
+            close: 3.0,
+            ..Default::default()
+        }]);
+
+        assert_eq!(replaced, 1);
+
+        assert_eq!(data.vals.len(), 2);
+        assert_eq!(data.vals[1].close, 3.0);
+    }
+
+    #[test]
+    fn test_max_vol() {
+        let mut data = Data::new_candle();
+        data.append(&mut vec![
+            Kline {
+                t_open: 0,
+                t_close: 59,
+                volume: 5.0,
+                ..Default::default()
+            },
+            Kline {
+                t_open: 60,
+                t_close: 119,
+                volume: 7.5,
+                ..Default::default()
+            },
+        ]);
+
+        assert_eq!(data.max_vol(), 7.5);
+    }
+}
@@ -0,0 +1,103 @@
+use egui::{
+    plot::{Line, Plot},
+    Color32,
+};
+
+use crate::{
+    sources::binance::{Depth, DepthLevel},
+    widgets::AppWidget,
+};
+
+use super::precision::Precision;
+
+/// Draws cumulative bid and ask volume of the order book by price.
+#[derive(Default, Clone)]
+pub struct DepthDrawer {
+    bids: Vec<[f64; 2]>,
+    asks: Vec<[f64; 2]>,
+    precision: Precision,
+}
+
+impl DepthDrawer {
+    pub fn set_depth(&mut self, depth: &Depth) {
+        self.bids = DepthDrawer::cumulative(&depth.bids);
+        self.asks = DepthDrawer::cumulative(&depth.asks);
+    }
+
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision;
+    }
+
+    pub fn clear(&mut self) {
+        self.bids = vec![];
+        self.asks = vec![];
+    }
+
+    /// Sums volume from the best price outwards and orders points by price.
+    fn cumulative(levels: &[DepthLevel]) -> Vec<[f64; 2]> {
+        let mut total = 0.0;
+        let mut res: Vec<[f64; 2]> = levels
+            .iter()
+            .map(|l| {
+                total += l.qty;
+                [l.price, total]
+            })
+            .collect();
+
+        res.sort_by(|a, b| a[0].total_cmp(&b[0]));
+
+        res
+    }
+}
+
+impl AppWidget for DepthDrawer {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let precision = self.precision;
+        Plot::new("depth")
+            .label_formatter(move |_, v| -> String {
+                format!(
+                    "price: {}\ndepth: {}",
+                    precision.price(v.x),
+                    precision.qty(v.y)
+                )
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(self.bids.clone())
+                        .color(Color32::LIGHT_GREEN)
+                        .fill(0.0)
+                        .name("bids"),
+                );
+                plot_ui.line(
+                    Line::new(self.asks.clone())
+                        .color(Color32::LIGHT_RED)
+                        .fill(0.0)
+                        .name("asks"),
+                );
+            });
+    }
+}
+
+#[cfg(test)]
+mod depth_drawer_tests {
+    use super::*;
+
+    #[test]
+    fn test_cumulative() {
+        let bids = vec![
+            DepthLevel {
+                price: 10.0,
+                qty: 1.0,
+            },
+            DepthLevel {
+                price: 9.0,
+                qty: 2.0,
+            },
+        ];
+
+        assert_eq!(
+            DepthDrawer::cumulative(&bids),
+            vec![[9.0, 3.0], [10.0, 1.0]]
+        );
+    }
+}
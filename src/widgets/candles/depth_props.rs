@@ -0,0 +1,162 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use egui::{ComboBox, DragValue, TopBottomPanel, Ui};
+use poll_promise::Promise;
+use tracing::{debug, error, info};
+
+use crate::sources::binance::{errors::ClientError, Depth, DEPTH_LIMITS};
+use crate::sources::MarketDataSource;
+use crate::widgets::AppWidget;
+
+use super::depth_drawer::DepthDrawer;
+use super::precision::Precision;
+
+const DEFAULT_LIMIT: usize = 100;
+const DEFAULT_REFRESH_SECS: u64 = 5;
+
+/// Order book depth chart for the selected symbol with optional periodic refresh.
+pub struct DepthProps {
+    drawer: DepthDrawer,
+    symbol: String,
+    limit: usize,
+    /// Limit of the running request. Snapshot of another limit is dropped and requested again.
+    requested_limit: usize,
+    auto_refresh: bool,
+    refresh_secs: u64,
+    last_update: Option<Instant>,
+    failed: bool,
+
+    depth_promise: Option<Promise<Result<Depth, ClientError>>>,
+    source: Arc<dyn MarketDataSource>,
+}
+
+impl DepthProps {
+    pub fn new(source: Arc<dyn MarketDataSource>) -> Self {
+        info!("initing widget depth");
+
+        Self {
+            source,
+            limit: DEFAULT_LIMIT,
+            requested_limit: DEFAULT_LIMIT,
+            auto_refresh: true,
+            refresh_secs: DEFAULT_REFRESH_SECS,
+            drawer: Default::default(),
+            symbol: Default::default(),
+            last_update: Default::default(),
+            failed: Default::default(),
+            depth_promise: Default::default(),
+        }
+    }
+
+    fn request_depth(&mut self) {
+        if self.symbol.is_empty() || self.depth_promise.is_some() {
+            return;
+        }
+
+        debug!(
+            "requesting depth: symbol: {}, limit: {}",
+            self.symbol, self.limit
+        );
+
+        let source = self.source.clone();
+        let symbol = self.symbol.clone();
+        let limit = self.limit;
+        self.requested_limit = limit;
+        self.depth_promise = Some(Promise::spawn_blocking(move || source.depth(symbol, limit)));
+        self.last_update = Some(Instant::now());
+    }
+
+    pub fn set_symbol(&mut self, symbol: String, precision: Precision) {
+        debug!("setting symbol: {symbol}");
+
+        self.symbol = symbol;
+        self.drawer.clear();
+        self.drawer.set_precision(precision);
+        self.depth_promise = None;
+        self.request_depth();
+    }
+
+    fn update(&mut self, ui: &Ui) {
+        if let Some(promise) = &self.depth_promise {
+            if let Some(result) = promise.ready() {
+                match result {
+                    _ if self.requested_limit != self.limit => {
+                        debug!("dropping depth of stale limit: {}", self.requested_limit);
+                    }
+                    Ok(depth) => {
+                        self.drawer.set_depth(depth);
+                        self.failed = false;
+                    }
+                    Err(err) => {
+                        error!("failed to get depth: {err}");
+                        self.failed = true;
+                    }
+                }
+
+                self.depth_promise = None;
+                if self.requested_limit != self.limit {
+                    self.request_depth();
+                }
+            }
+        }
+
+        if !self.auto_refresh {
+            return;
+        }
+
+        let period = Duration::from_secs(self.refresh_secs);
+        match self.last_update {
+            Some(last) if last.elapsed() < period => {
+                ui.ctx().request_repaint_after(period - last.elapsed());
+            }
+            _ => self.request_depth(),
+        }
+    }
+}
+
+impl AppWidget for DepthProps {
+    fn show(&mut self, ui: &mut Ui) {
+        self.update(ui);
+
+        TopBottomPanel::top("depth_toolbar").show_inside(ui, |ui| {
+            ui.horizontal(|ui| {
+                let mut limit = self.limit;
+                ComboBox::from_label("levels")
+                    .selected_text(limit.to_string())
+                    .show_ui(ui, |ui| {
+                        DEPTH_LIMITS.iter().for_each(|l| {
+                            ui.selectable_value(&mut limit, *l, l.to_string());
+                        });
+                    });
+                if limit != self.limit {
+                    info!("setting depth limit to: {limit}");
+                    self.limit = limit;
+                    self.request_depth();
+                }
+
+                ui.checkbox(&mut self.auto_refresh, "auto refresh");
+                ui.add_enabled(
+                    self.auto_refresh,
+                    DragValue::new(&mut self.refresh_secs)
+                        .clamp_range(1..=60)
+                        .suffix("s"),
+                );
+
+                if ui.button("refresh").clicked() {
+                    self.request_depth();
+                }
+
+                if self.depth_promise.is_some() {
+                    ui.spinner();
+                }
+
+                if self.failed {
+                    ui.label("Failed to get order book from Binance");
+                }
+            });
+        });
+
+        self.drawer.show(ui);
+    }
+}
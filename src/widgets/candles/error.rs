@@ -6,3 +6,65 @@ quick_error! {
         Error {}
     }
 }
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum CacheError {
+        Io(err: std::io::Error) {
+            from()
+            display("{}", err)
+        }
+        Serialization(err: serde_json::Error) {
+            from()
+            display("{}", err)
+        }
+    }
+}
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum ExportError {
+        Io(err: std::io::Error) {
+            from()
+            display("{}", err)
+        }
+        Csv(err: csv::Error) {
+            from()
+            display("{}", err)
+        }
+    }
+}
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum ImportError {
+        Io(err: std::io::Error) {
+            from()
+            display("{}", err)
+        }
+        Zip(err: zip::result::ZipError) {
+            from()
+            display("{}", err)
+        }
+        Csv(err: csv::Error) {
+            from()
+            display("{}", err)
+        }
+        Cache(err: CacheError) {
+            from()
+            display("{}", err)
+        }
+        InvalidName(name: String) {
+            display("failed to parse symbol from file name: {}", name)
+        }
+        InvalidRow(row: usize) {
+            display("invalid kline at row: {}", row)
+        }
+        UnknownInterval {
+            display("failed to infer klines interval")
+        }
+        Empty {
+            display("no klines found")
+        }
+    }
+}
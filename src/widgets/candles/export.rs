@@ -0,0 +1,114 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tracing::debug;
+
+use super::error::ExportError;
+
+/// Writes rows to csv file in the working directory and returns absolute path of the file.
+pub fn write_csv<'a, T, I>(name: &str, rows: I) -> Result<PathBuf, ExportError>
+where
+    T: Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let path = Path::new(name);
+    let f = File::create(path)?;
+
+    let abs_path = path.canonicalize()?;
+    debug!("saving to file: {}", abs_path.display());
+
+    let mut wtr = csv::Writer::from_writer(f);
+    for row in rows {
+        wtr.serialize(row)?;
+    }
+    wtr.flush()?;
+
+    Ok(abs_path)
+}
+
+/// Writes rows with extra columns appended to them, e.g. indicators computed for every kline.
+///
+/// Columns must have a value for every row.
+pub fn write_csv_with_columns<'a, T, I>(
+    name: &str,
+    rows: I,
+    columns: &[(String, Vec<String>)],
+) -> Result<PathBuf, ExportError>
+where
+    T: Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    // header of serialized rows is known only after serde writes it
+    let mut buf = vec![];
+    let mut serialized = csv::Writer::from_writer(&mut buf);
+    for row in rows {
+        serialized.serialize(row)?;
+    }
+    serialized.flush()?;
+    drop(serialized);
+
+    let mut rdr = csv::Reader::from_reader(buf.as_slice());
+    let mut headers = rdr.headers()?.clone();
+    columns
+        .iter()
+        .for_each(|(name, _)| headers.push_field(name));
+
+    let path = Path::new(name);
+    let f = File::create(path)?;
+
+    let abs_path = path.canonicalize()?;
+    debug!("saving to file: {}", abs_path.display());
+
+    let mut wtr = csv::Writer::from_writer(f);
+    wtr.write_record(&headers)?;
+    for (i, record) in rdr.records().enumerate() {
+        let mut record = record?;
+        columns
+            .iter()
+            .for_each(|(_, vals)| record.push_field(&vals[i]));
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()?;
+
+    Ok(abs_path)
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        a: i64,
+        b: String,
+    }
+
+    #[test]
+    fn test_write_csv_with_columns() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = dir.join("rows.csv");
+
+        let rows = [
+            Row {
+                a: 1,
+                b: "x".to_string(),
+            },
+            Row {
+                a: 2,
+                b: "y".to_string(),
+            },
+        ];
+        let columns = vec![("c".to_string(), vec!["".to_string(), "1.5".to_string()])];
+
+        let path = write_csv_with_columns(name.to_str().unwrap(), rows.iter(), &columns).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "a,b,c\n1,x,\n2,y,1.5\n"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,479 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use csv::StringRecord;
+use tracing::{debug, info};
+use zip::ZipArchive;
+
+use crate::sources::binance::{Interval, Kline, Market, PriceKind};
+
+use super::{
+    bounds::Bounds,
+    error::ImportError,
+    kline_cache::{CacheEntry, KlineCache},
+    step::Step,
+};
+
+/// Timestamps greater than this are in microseconds, which binance uses in newer dumps.
+const MAX_MILLIS_TS: i64 = 100_000_000_000_000;
+
+/// Klines read from a binance public data dump file.
+#[derive(Debug, Clone)]
+pub struct ImportedKlines {
+    pub market: Market,
+    pub kind: PriceKind,
+    pub symbol: String,
+    pub interval: Interval,
+    pub bounds: Bounds,
+    pub klines: Vec<Kline>,
+    /// Klines were written by export and represent a complete dataset.
+    pub exported: bool,
+}
+
+/// Reads klines from a binance data dump or from a csv file written by export.
+pub fn import_file(path: &Path) -> Result<ImportedKlines, ImportError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match parse_dump_name(&name) {
+        Some((symbol, interval)) => import_dump(path, symbol, interval),
+        None => import_export(path, &name),
+    }
+}
+
+/// Reads klines from a zip archive or a csv file from https://data.binance.vision.
+///
+/// Symbol and interval are taken from the file name, e.g. `BTCUSDT-1m-2022-01.zip`.
+fn import_dump(
+    path: &Path,
+    symbol: String,
+    interval: Interval,
+) -> Result<ImportedKlines, ImportError> {
+    debug!("importing klines: symbol: {symbol}, interval: {interval:?}, file: {path:?}");
+
+    let f = File::open(path)?;
+    let mut klines = match path.extension().and_then(|e| e.to_str()) {
+        Some("zip") => {
+            let mut archive = ZipArchive::new(BufReader::new(f))?;
+            let mut res = vec![];
+            for i in 0..archive.len() {
+                let entry = archive.by_index(i)?;
+                if !entry.name().ends_with(".csv") {
+                    continue;
+                }
+
+                res.append(&mut parse_rows(entry)?);
+            }
+
+            res
+        }
+        _ => parse_rows(f)?,
+    };
+
+    if klines.is_empty() {
+        return Err(ImportError::Empty);
+    }
+
+    klines.sort_by_key(|k| k.t_open);
+    klines.dedup_by_key(|k| k.t_open);
+
+    let bounds = Bounds(
+        klines.first().unwrap().t_open,
+        Step::from(interval).advance(klines.last().unwrap().t_open, 1),
+    );
+
+    info!(
+        "imported {} klines for {symbol} {interval:?}; covered bounds: {bounds:?}",
+        klines.len()
+    );
+
+    Ok(ImportedKlines {
+        market: Market::Spot,
+        kind: PriceKind::Last,
+        symbol,
+        interval,
+        bounds,
+        klines,
+        exported: false,
+    })
+}
+
+/// Reads klines from a csv file written by export, e.g. `BTCUSDT_1640995200_1641081600_Minute.csv`
+/// or `um_BTCUSDT_Mark_1640995200_1641081600_Minute.csv` for futures and other price kinds.
+///
+/// Symbol is taken from the file name. Interval and range are taken from the file name
+/// if it was not renamed, otherwise they are inferred from the klines.
+fn import_export(path: &Path, name: &str) -> Result<ImportedKlines, ImportError> {
+    let ExportName {
+        market,
+        kind,
+        symbol,
+        bounds: name_bounds,
+        interval: name_interval,
+    } = parse_export_name(name).ok_or_else(|| ImportError::InvalidName(name.to_string()))?;
+
+    debug!("importing exported klines: symbol: {symbol}, file: {path:?}");
+
+    let mut rdr = csv::Reader::from_path(path)?;
+    let mut klines = vec![];
+    for (i, row) in rdr.deserialize::<Kline>().enumerate() {
+        klines.push(row.map_err(|_| ImportError::InvalidRow(i))?);
+    }
+
+    if klines.is_empty() {
+        return Err(ImportError::Empty);
+    }
+
+    klines.sort_by_key(|k| k.t_open);
+    klines.dedup_by_key(|k| k.t_open);
+
+    let interval = name_interval
+        .or_else(|| infer_interval(&klines[0]))
+        .ok_or(ImportError::UnknownInterval)?;
+    let bounds = name_bounds.unwrap_or_else(|| {
+        Bounds(
+            klines.first().unwrap().t_open,
+            Step::from(interval).advance(klines.last().unwrap().t_open, 1),
+        )
+    });
+
+    info!(
+        "imported {} exported klines for {symbol} {interval:?}; range: {bounds:?}",
+        klines.len()
+    );
+
+    Ok(ImportedKlines {
+        market,
+        kind,
+        symbol,
+        interval,
+        bounds,
+        klines,
+        exported: true,
+    })
+}
+
+/// Merges imported klines into cached ones replacing klines with the same open time.
+pub fn merge_into_cache(cache: &KlineCache, imported: &ImportedKlines) -> Result<(), ImportError> {
+    let mut entry = cache
+        .load(&imported.symbol, imported.interval)
+        .unwrap_or_default();
+
+    let mut klines = imported.klines.clone();
+    klines.append(&mut entry.klines);
+    // stable sort keeps imported klines first so they win deduplication
+    klines.sort_by_key(|k| k.t_open);
+    klines.dedup_by_key(|k| k.t_open);
+
+    let entry = CacheEntry {
+        bounds: entry.bounds.merge_single(imported.bounds),
+        klines,
+    };
+
+    cache.save(&imported.symbol, imported.interval, &entry)?;
+
+    Ok(())
+}
+
+/// Parses symbol and interval from names like `BTCUSDT-1m-2022-01-15.zip`.
+fn parse_dump_name(name: &str) -> Option<(String, Interval)> {
+    let mut parts = name.split('-');
+    let symbol = parts.next().filter(|s| !s.is_empty())?;
+    let interval = Interval::parse(parts.next()?)?;
+
+    Some((symbol.to_uppercase(), interval))
+}
+
+/// Parts of the export file name.
+#[derive(Debug, PartialEq)]
+struct ExportName {
+    market: Market,
+    kind: PriceKind,
+    symbol: String,
+    bounds: Option<Bounds>,
+    interval: Option<Interval>,
+}
+
+/// Name klines of the symbol are cached and exported under. Spot symbols keep plain names.
+pub fn export_symbol(market: Market, kind: PriceKind, symbol: &str) -> String {
+    match (market, kind) {
+        (Market::Spot, _) => symbol.to_string(),
+        (market, PriceKind::Last) => format!("{}_{symbol}", market.code()),
+        (market, kind) => format!("{}_{symbol}_{kind:?}", market.code()),
+    }
+}
+
+/// Parses names like `BTCUSDT_1640995200_1641081600_Minute.csv` optionally prefixed with
+/// market code and followed by price kind the same way klines are named in the cache,
+/// e.g. `um_BTCUSDT_Mark_1640995200_1641081600_Minute.csv`.
+///
+/// Only symbol is required, range and interval are None when they can not be parsed.
+fn parse_export_name(name: &str) -> Option<ExportName> {
+    let stem = name.strip_suffix(".csv").unwrap_or(name);
+    let mut parts: &[&str] = &stem.split('_').collect::<Vec<_>>();
+
+    let market = match Market::ALL
+        .iter()
+        .find(|m| Some(&m.code()) == parts.first())
+    {
+        Some(market) => {
+            parts = &parts[1..];
+            *market
+        }
+        None => Market::Spot,
+    };
+
+    let symbol = parts.first().filter(|s| !s.is_empty())?.to_uppercase();
+    parts = &parts[1..];
+
+    let kind = match PriceKind::ALL
+        .iter()
+        .find(|k| Some(&format!("{k:?}").as_str()) == parts.first())
+    {
+        Some(kind) => {
+            parts = &parts[1..];
+            *kind
+        }
+        None => PriceKind::Last,
+    };
+
+    let mut export_name = ExportName {
+        market,
+        kind,
+        symbol,
+        bounds: None,
+        interval: None,
+    };
+    if parts.len() != 3 {
+        return Some(export_name);
+    }
+
+    export_name.bounds = match (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
+        (Ok(start), Ok(end)) if start < end => Some(Bounds(start * 1000, end * 1000)),
+        _ => None,
+    };
+    export_name.interval = Interval::ALL
+        .iter()
+        .find(|i| format!("{i:?}") == parts[2])
+        .copied();
+
+    Some(export_name)
+}
+
+/// Finds interval matching kline duration.
+fn infer_interval(k: &Kline) -> Option<Interval> {
+    let duration = k.t_close - k.t_open + 1;
+    let month = Step::from(Interval::Month).advance(k.t_open, 1) - k.t_open;
+    if duration == month {
+        return Some(Interval::Month);
+    }
+
+    Interval::ALL
+        .iter()
+        .find(|i| i.millis() == Some(duration))
+        .copied()
+}
+
+fn parse_rows<R: Read>(r: R) -> Result<Vec<Kline>, ImportError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(r);
+
+    let mut res = vec![];
+    for (i, record) in rdr.records().enumerate() {
+        let record = record?;
+
+        // some dumps start with a header row
+        if i == 0 && matches!(record.get(0), Some(f) if f.parse::<i64>().is_err()) {
+            continue;
+        }
+
+        res.push(parse_row(&record).ok_or(ImportError::InvalidRow(i))?);
+    }
+
+    Ok(res)
+}
+
+fn parse_row(record: &StringRecord) -> Option<Kline> {
+    let int = |i: usize| record.get(i)?.trim().parse::<i64>().ok();
+    let float = |i: usize| record.get(i)?.trim().parse::<f64>().ok();
+    let ts = |i: usize| {
+        int(i).map(|ts| match ts > MAX_MILLIS_TS {
+            true => ts / 1000,
+            false => ts,
+        })
+    };
+
+    Some(Kline {
+        t_open: ts(0)?,
+        open: float(1)?,
+        high: float(2)?,
+        low: float(3)?,
+        close: float(4)?,
+        volume: float(5)?,
+        t_close: ts(6)?,
+        quote_asset_volume: float(7)?,
+        number_of_trades: int(8)?,
+        taker_buy_base_asset_volume: float(9)?,
+        taker_buy_quote_asset_volume: float(10)?,
+    })
+}
+
+#[cfg(test)]
+mod import_tests {
+    use super::*;
+
+    const ROWS: &str = "open_time,open,high,low,close,volume,close_time,quote_volume,count,taker_buy_volume,taker_buy_quote_volume,ignore
+1640995200000,46216.93,46271.08,46208.37,46250.00,40.57574,1640995259999,1876795.78,1060,19.55183,904305.30,0
+1640995260000000,46250.00,46344.23,46234.39,46312.76,42.38106,1640995319999999,1962389.05,1123,25.51483,1181387.25,0
+";
+
+    #[test]
+    fn test_parse_dump_name() {
+        assert_eq!(
+            parse_dump_name("BTCUSDT-1m-2022-01.zip"),
+            Some(("BTCUSDT".to_string(), Interval::Minute))
+        );
+        assert_eq!(
+            parse_dump_name("ethbtc-1M-2022-01-15.csv"),
+            Some(("ETHBTC".to_string(), Interval::Month))
+        );
+        assert_eq!(parse_dump_name("BTCUSDT_1m.csv"), None);
+        assert_eq!(parse_dump_name("BTCUSDT-2m-2022-01.zip"), None);
+    }
+
+    #[test]
+    fn test_parse_export_name() {
+        assert_eq!(
+            parse_export_name("BTCUSDT_1640995200_1641081600_Minutes15.csv"),
+            Some(ExportName {
+                market: Market::Spot,
+                kind: PriceKind::Last,
+                symbol: "BTCUSDT".to_string(),
+                bounds: Some(Bounds(1640995200000, 1641081600000)),
+                interval: Some(Interval::Minutes15)
+            })
+        );
+        assert_eq!(
+            parse_export_name("um_BTCUSDT_Mark_1640995200_1641081600_Hour.csv"),
+            Some(ExportName {
+                market: Market::UsdFutures,
+                kind: PriceKind::Mark,
+                symbol: "BTCUSDT".to_string(),
+                bounds: Some(Bounds(1640995200000, 1641081600000)),
+                interval: Some(Interval::Hour)
+            })
+        );
+        assert_eq!(
+            parse_export_name("um_ETHUSDT_1640995200_1641081600_Day.csv").map(|n| n.market),
+            Some(Market::UsdFutures)
+        );
+        assert_eq!(
+            parse_export_name("btcusdt_shared.csv"),
+            Some(ExportName {
+                market: Market::Spot,
+                kind: PriceKind::Last,
+                symbol: "BTCUSDT".to_string(),
+                bounds: None,
+                interval: None
+            })
+        );
+        assert_eq!(parse_export_name("_.csv"), None);
+        assert_eq!(parse_export_name("um.csv"), None);
+    }
+
+    #[test]
+    fn test_export_symbol() {
+        assert_eq!(
+            export_symbol(Market::Spot, PriceKind::Mark, "BTCUSDT"),
+            "BTCUSDT"
+        );
+        assert_eq!(
+            export_symbol(Market::UsdFutures, PriceKind::Last, "BTCUSDT"),
+            "um_BTCUSDT"
+        );
+
+        let name = format!(
+            "{}_1640995200_1641081600_Hour.csv",
+            export_symbol(Market::UsdFutures, PriceKind::Index, "BTCUSDT")
+        );
+        let parsed = parse_export_name(&name).unwrap();
+        assert_eq!(parsed.market, Market::UsdFutures);
+        assert_eq!(parsed.kind, PriceKind::Index);
+        assert_eq!(parsed.symbol, "BTCUSDT");
+    }
+
+    #[test]
+    fn test_import_export() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ETHBTC_shared.csv");
+        let klines = vec![
+            Kline {
+                t_open: 3_600_000,
+                t_close: 7_199_999,
+                ..Default::default()
+            },
+            Kline {
+                t_open: 0,
+                t_close: 3_599_999,
+                ..Default::default()
+            },
+        ];
+        let mut wtr = csv::Writer::from_path(&path).unwrap();
+        klines.iter().for_each(|k| wtr.serialize(k).unwrap());
+        wtr.flush().unwrap();
+
+        let imported = import_file(&path).unwrap();
+
+        assert!(imported.exported);
+        assert_eq!(imported.symbol, "ETHBTC");
+        assert_eq!(imported.interval, Interval::Hour);
+        assert_eq!(imported.bounds, Bounds(0, 7_200_000));
+        assert_eq!(imported.klines[0].t_open, 0);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_rows() {
+        let klines = parse_rows(ROWS.as_bytes()).unwrap();
+
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].t_open, 1640995200000);
+        assert_eq!(klines[0].number_of_trades, 1060);
+        assert_eq!(klines[1].t_open, 1640995260000);
+        assert_eq!(klines[1].t_close, 1640995319999);
+
+        assert!(matches!(
+            parse_rows("1,2,3".as_bytes()),
+            Err(ImportError::InvalidRow(0))
+        ));
+    }
+
+    #[test]
+    fn test_import_csv_merge_into_cache() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("BTCUSDT-1m-2022-01-01.csv");
+        std::fs::write(&path, ROWS).unwrap();
+
+        let imported = import_file(&path).unwrap();
+        assert_eq!(imported.symbol, "BTCUSDT");
+        assert_eq!(imported.bounds, Bounds(1640995200000, 1640995320000));
+
+        let cache = KlineCache::new(dir.join("cache"));
+        merge_into_cache(&cache, &imported).unwrap();
+        merge_into_cache(&cache, &imported).unwrap();
+
+        let entry = cache.load("BTCUSDT", Interval::Minute).unwrap();
+        assert_eq!(entry.klines.len(), 2);
+        assert!(entry.bounds.contains(1640995200000));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,548 @@
+use egui::{DragValue, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::sources::binance::Kline;
+
+use super::precision::Precision;
+
+const MILLIS_DAY: i64 = 24 * 60 * 60 * 1000;
+const MAX_PERIOD: usize = 1000;
+
+/// Technical indicator with its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Indicator {
+    /// Simple moving average of close prices.
+    Sma { period: usize },
+    /// Exponential moving average of close prices seeded with the simple average.
+    Ema { period: usize },
+    /// Volume weighted average of typical price restarting every UTC day.
+    Vwap,
+    /// Simple moving average with bands k standard deviations away from it.
+    Bollinger { period: usize, k: f64 },
+    /// Relative strength index with Wilder smoothing.
+    Rsi { period: usize },
+    /// Difference of fast and slow emas with its signal ema and histogram.
+    Macd {
+        fast: usize,
+        slow: usize,
+        signal: usize,
+    },
+    /// Average true range with Wilder smoothing.
+    Atr { period: usize },
+}
+
+impl Indicator {
+    pub const ALL: [Indicator; 7] = [
+        Indicator::Sma { period: 20 },
+        Indicator::Ema { period: 20 },
+        Indicator::Vwap,
+        Indicator::Bollinger { period: 20, k: 2.0 },
+        Indicator::Rsi { period: 14 },
+        Indicator::Macd {
+            fast: 12,
+            slow: 26,
+            signal: 9,
+        },
+        Indicator::Atr { period: 14 },
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Indicator::Sma { .. } => "SMA",
+            Indicator::Ema { .. } => "EMA",
+            Indicator::Vwap => "VWAP",
+            Indicator::Bollinger { .. } => "BB",
+            Indicator::Rsi { .. } => "RSI",
+            Indicator::Macd { .. } => "MACD",
+            Indicator::Atr { .. } => "ATR",
+        }
+    }
+
+    /// Name with parameters, e.g. `MACD(12, 26, 9)`.
+    pub fn label(&self) -> String {
+        match self {
+            Indicator::Sma { period }
+            | Indicator::Ema { period }
+            | Indicator::Rsi { period }
+            | Indicator::Atr { period } => format!("{}({period})", self.name()),
+            Indicator::Vwap => self.name().to_string(),
+            Indicator::Bollinger { period, k } => format!("{}({period}, {k})", self.name()),
+            Indicator::Macd { fast, slow, signal } => {
+                format!("{}({fast}, {slow}, {signal})", self.name())
+            }
+        }
+    }
+
+    /// Price scale indicators are drawn over candles, oscillators in their own panels.
+    pub fn overlay(&self) -> bool {
+        match self {
+            Indicator::Sma { .. }
+            | Indicator::Ema { .. }
+            | Indicator::Vwap
+            | Indicator::Bollinger { .. } => true,
+            Indicator::Rsi { .. } | Indicator::Macd { .. } | Indicator::Atr { .. } => false,
+        }
+    }
+
+    /// Names of lines in the order they are stored in points.
+    pub fn lines(&self) -> &'static [&'static str] {
+        match self {
+            Indicator::Bollinger { .. } => &["mid", "upper", "lower"],
+            Indicator::Macd { .. } => &["macd", "signal", "hist"],
+            _ => &[""],
+        }
+    }
+
+    /// Csv column names of the lines, e.g. `bb_20_2_upper`.
+    pub fn columns(&self) -> Vec<String> {
+        let prefix = self
+            .label()
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| match c {
+                '(' | ',' => Some('_'),
+                ' ' | ')' => None,
+                c => Some(c),
+            })
+            .collect::<String>();
+
+        let name = self.name().to_lowercase();
+        self.lines()
+            .iter()
+            .map(|line| match line.is_empty() || *line == name {
+                true => prefix.clone(),
+                false => format!("{prefix}_{line}"),
+            })
+            .collect()
+    }
+
+    /// Formats value of the indicator, rsi is in percents while others are in quote asset.
+    pub fn format(&self, v: f64, precision: &Precision) -> String {
+        match self {
+            Indicator::Rsi { .. } => format!("{v:.2}"),
+            _ => precision.price(v),
+        }
+    }
+
+    /// Shows parameter editors returning true if any parameter changed.
+    pub fn params_ui(&mut self, ui: &mut Ui) -> bool {
+        let period = |ui: &mut Ui, v: &mut usize, prefix: &str| {
+            ui.add(DragValue::new(v).clamp_range(1..=MAX_PERIOD).prefix(prefix))
+                .changed()
+        };
+
+        match self {
+            Indicator::Sma { period: p }
+            | Indicator::Ema { period: p }
+            | Indicator::Rsi { period: p }
+            | Indicator::Atr { period: p } => period(ui, p, "period: "),
+            Indicator::Vwap => false,
+            Indicator::Bollinger { period: p, k } => {
+                let changed = period(ui, p, "period: ");
+                ui.add(
+                    DragValue::new(k)
+                        .clamp_range(0.1..=10.0)
+                        .speed(0.1)
+                        .prefix("k: "),
+                )
+                .changed()
+                    || changed
+            }
+            Indicator::Macd { fast, slow, signal } => {
+                let mut changed = period(ui, fast, "fast: ");
+                changed |= period(ui, slow, "slow: ");
+                changed |= period(ui, signal, "signal: ");
+                changed
+            }
+        }
+    }
+
+    /// Computes point of the kline at index i from klines and points before it.
+    fn step(&self, klines: &[Kline], points: &[Point], i: usize) -> Point {
+        let k = &klines[i];
+        let prev = i.checked_sub(1).and_then(|j| points.get(j));
+        let carry = |idx: usize| prev.and_then(|p| p.carry[idx]);
+        let window = |period: usize| match period > 0 && i + 1 >= period {
+            true => Some(&klines[i + 1 - period..=i]),
+            false => None,
+        };
+        let mean_close = |period: usize| window(period).map(|w| mean(w.iter().map(|k| k.close)));
+
+        match *self {
+            Indicator::Sma { period } => Point::new([mean_close(period), None, None], [None; 3]),
+            Indicator::Ema { period } => {
+                let ema = ema(carry(0), period, k.close, || mean_close(period));
+                Point::new([ema, None, None], [ema, None, None])
+            }
+            Indicator::Vwap => {
+                let day = k.t_open.div_euclid(MILLIS_DAY) as f64;
+                let typical = (k.high + k.low + k.close) / 3.0;
+                let (pv, v) = match carry(2) == Some(day) {
+                    true => (carry(0).unwrap_or_default(), carry(1).unwrap_or_default()),
+                    false => (0.0, 0.0),
+                };
+                let (pv, v) = (pv + typical * k.volume, v + k.volume);
+
+                let vwap = match v > 0.0 {
+                    true => pv / v,
+                    false => typical,
+                };
+                Point::new([Some(vwap), None, None], [Some(pv), Some(v), Some(day)])
+            }
+            Indicator::Bollinger { period, k: width } => match window(period) {
+                Some(w) => {
+                    let mid = mean(w.iter().map(|k| k.close));
+                    let sd = mean(w.iter().map(|k| (k.close - mid).powi(2))).sqrt();
+                    Point::new(
+                        [Some(mid), Some(mid + width * sd), Some(mid - width * sd)],
+                        [None; 3],
+                    )
+                }
+                None => Point::default(),
+            },
+            Indicator::Rsi { period } => {
+                if i == 0 || period == 0 {
+                    return Point::default();
+                }
+
+                let (gain, loss) = gain_loss(&klines[i - 1], k);
+                let avg = match carry(0).zip(carry(1)) {
+                    Some((g, l)) => Some((wilder(g, gain, period), wilder(l, loss, period))),
+                    None if i >= period => {
+                        let changes = (i + 1 - period..=i)
+                            .map(|j| gain_loss(&klines[j - 1], &klines[j]))
+                            .collect::<Vec<_>>();
+                        Some((
+                            mean(changes.iter().map(|(g, _)| *g)),
+                            mean(changes.iter().map(|(_, l)| *l)),
+                        ))
+                    }
+                    None => None,
+                };
+
+                let rsi = avg.map(|(g, l)| match l > 0.0 {
+                    true => 100.0 - 100.0 / (1.0 + g / l),
+                    false => 100.0,
+                });
+                Point::new(
+                    [rsi, None, None],
+                    [avg.map(|(g, _)| g), avg.map(|(_, l)| l), None],
+                )
+            }
+            Indicator::Macd { fast, slow, signal } => {
+                let fast_ema = ema(carry(0), fast, k.close, || mean_close(fast));
+                let slow_ema = ema(carry(1), slow, k.close, || mean_close(slow));
+                let macd = fast_ema.zip(slow_ema).map(|(f, s)| f - s);
+                let signal_ema = macd.and_then(|m| {
+                    ema(carry(2), signal, m, || {
+                        // seeded with the average of the first signal macd values
+                        let start = (i + 1).checked_sub(signal)?;
+                        let vals = points[start..i]
+                            .iter()
+                            .map(|p| p.lines[0])
+                            .collect::<Option<Vec<_>>>()?;
+                        Some(mean(vals.into_iter().chain([m])))
+                    })
+                });
+                let hist = macd.zip(signal_ema).map(|(m, s)| m - s);
+
+                Point::new([macd, signal_ema, hist], [fast_ema, slow_ema, signal_ema])
+            }
+            Indicator::Atr { period } => {
+                let tr = true_range(klines, i);
+                let atr = match carry(0) {
+                    Some(atr) => Some(wilder(atr, tr, period)),
+                    None => window(period)
+                        .map(|_| mean((i + 1 - period..=i).map(|j| true_range(klines, j)))),
+                };
+                Point::new([atr, None, None], [atr, None, None])
+            }
+        }
+    }
+}
+
+/// Indicator values at a kline together with smoothed values the next kline continues from.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Point {
+    /// Values in the order of indicator lines. None while the indicator warms up.
+    pub lines: [Option<f64>; 3],
+    carry: [Option<f64>; 3],
+}
+
+impl Point {
+    fn new(lines: [Option<f64>; 3], carry: [Option<f64>; 3]) -> Self {
+        Self { lines, carry }
+    }
+}
+
+/// Indicator points computed for every kline.
+#[derive(Debug, Clone)]
+pub struct Series {
+    indicator: Indicator,
+    points: Vec<Point>,
+}
+
+impl Series {
+    pub fn new(indicator: Indicator) -> Self {
+        Self {
+            indicator,
+            points: Default::default(),
+        }
+    }
+
+    pub fn indicator(&self) -> Indicator {
+        self.indicator
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// Recomputes points starting from the kline at index from keeping points before it.
+    ///
+    /// Klines before from must be the same the series was computed for, so appending
+    /// klines to the end costs only the new klines.
+    pub fn update(&mut self, klines: &[Kline], from: usize) {
+        let from = from.min(self.points.len()).min(klines.len());
+        self.points.truncate(from);
+
+        for i in from..klines.len() {
+            let point = self.indicator.step(klines, &self.points, i);
+            self.points.push(point);
+        }
+    }
+}
+
+/// Computes indicator points for all klines.
+pub fn compute(indicator: Indicator, klines: &[Kline]) -> Vec<Point> {
+    let mut series = Series::new(indicator);
+    series.update(klines, 0);
+    series.points
+}
+
+/// Csv columns of all indicator lines with values formatted for every kline.
+pub fn columns(
+    indicators: &[Indicator],
+    klines: &[Kline],
+    precision: &Precision,
+) -> Vec<(String, Vec<String>)> {
+    indicators
+        .iter()
+        .flat_map(|indicator| {
+            let points = compute(*indicator, klines);
+            indicator
+                .columns()
+                .into_iter()
+                .enumerate()
+                .map(|(line, name)| {
+                    let vals = points
+                        .iter()
+                        .map(|p| match p.lines[line] {
+                            Some(v) => indicator.format(v, precision),
+                            None => String::new(),
+                        })
+                        .collect();
+                    (name, vals)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Shows editable list of indicators returning true if it changed.
+pub fn settings_ui(ui: &mut Ui, indicators: &mut Vec<Indicator>) -> bool {
+    let mut changed = false;
+    let mut removed = None;
+    indicators
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, indicator)| {
+            ui.horizontal(|ui| {
+                ui.label(indicator.name());
+                changed |= indicator.params_ui(ui);
+                if ui.button("✖").on_hover_text("Remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+        });
+
+    if let Some(i) = removed {
+        indicators.remove(i);
+        changed = true;
+    }
+
+    ui.menu_button("add", |ui| {
+        Indicator::ALL.iter().for_each(|indicator| {
+            if ui.button(indicator.label()).clicked() {
+                indicators.push(*indicator);
+                changed = true;
+                ui.close_menu();
+            }
+        });
+    });
+
+    changed
+}
+
+fn mean(vals: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = vals.fold((0.0, 0), |(sum, n), v| (sum + v, n + 1));
+    match n {
+        0 => 0.0,
+        n => sum / n as f64,
+    }
+}
+
+/// Continues ema from prev or seeds it when there is no previous value yet.
+fn ema(
+    prev: Option<f64>,
+    period: usize,
+    value: f64,
+    seed: impl FnOnce() -> Option<f64>,
+) -> Option<f64> {
+    match prev {
+        Some(prev) => Some(prev + (value - prev) * 2.0 / (period as f64 + 1.0)),
+        None => seed(),
+    }
+}
+
+/// Wilder smoothing used by rsi and atr.
+fn wilder(prev: f64, value: f64, period: usize) -> f64 {
+    (prev * (period as f64 - 1.0) + value) / period as f64
+}
+
+fn gain_loss(prev: &Kline, k: &Kline) -> (f64, f64) {
+    let change = k.close - prev.close;
+    (change.max(0.0), (-change).max(0.0))
+}
+
+fn true_range(klines: &[Kline], i: usize) -> f64 {
+    let k = &klines[i];
+    match i.checked_sub(1).map(|j| klines[j].close) {
+        Some(prev_close) => (k.high - k.low)
+            .max((k.high - prev_close).abs())
+            .max((k.low - prev_close).abs()),
+        None => k.high - k.low,
+    }
+}
+
+#[cfg(test)]
+mod indicators_tests {
+    use crate::widgets::candles::test_klines::minute;
+
+    use super::*;
+
+    fn klines(closes: &[f64]) -> Vec<Kline> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Kline {
+                volume: 1.0 + i as f64,
+                ..minute(i as i64, *c, c + 1.0, c - 1.0, *c)
+            })
+            .collect()
+    }
+
+    fn line(points: &[Point], line: usize) -> Vec<Option<f64>> {
+        points.iter().map(|p| p.lines[line]).collect()
+    }
+
+    #[test]
+    fn test_sma_ema() {
+        let klines = klines(&[1.0, 2.0, 3.0, 4.0]);
+
+        let sma = compute(Indicator::Sma { period: 3 }, &klines);
+        assert_eq!(line(&sma, 0), vec![None, None, Some(2.0), Some(3.0)]);
+
+        let ema = compute(Indicator::Ema { period: 3 }, &klines);
+        assert_eq!(line(&ema, 0), vec![None, None, Some(2.0), Some(3.0)]);
+
+        let ema = compute(Indicator::Ema { period: 1 }, &klines);
+        assert_eq!(ema[3].lines[0], Some(4.0));
+    }
+
+    #[test]
+    fn test_bollinger() {
+        let klines = klines(&[1.0, 3.0]);
+
+        let bb = compute(Indicator::Bollinger { period: 2, k: 2.0 }, &klines);
+        assert_eq!(bb[1].lines, [Some(2.0), Some(4.0), Some(0.0)]);
+    }
+
+    #[test]
+    fn test_rsi() {
+        let rising = compute(Indicator::Rsi { period: 2 }, &klines(&[1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(line(&rising, 0), vec![None, None, Some(100.0), Some(100.0)]);
+
+        let mixed = compute(Indicator::Rsi { period: 2 }, &klines(&[1.0, 3.0, 2.0]));
+        // average gain 1, average loss 0.5
+        assert!((mixed[2].lines[0].unwrap() - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_macd() {
+        let klines = klines(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        let macd = compute(
+            Indicator::Macd {
+                fast: 1,
+                slow: 2,
+                signal: 2,
+            },
+            &klines,
+        );
+        assert_eq!(
+            line(&macd, 0),
+            vec![None, Some(0.5), Some(0.5), Some(0.5), Some(0.5)]
+        );
+        assert_eq!(line(&macd, 1)[..3], [None, None, Some(0.5)]);
+        assert_eq!(macd[4].lines[2], Some(0.0));
+    }
+
+    #[test]
+    fn test_atr_vwap() {
+        let klines = klines(&[1.0, 4.0]);
+
+        let atr = compute(Indicator::Atr { period: 2 }, &klines);
+        // true ranges are 2 and 4 because of the gap between closes
+        assert_eq!(line(&atr, 0), vec![None, Some(3.0)]);
+
+        let vwap = compute(Indicator::Vwap, &klines);
+        assert_eq!(vwap[1].lines[0], Some((1.0 + 4.0 * 2.0) / 3.0));
+    }
+
+    #[test]
+    fn test_update_incremental() {
+        let indicator = Indicator::Rsi { period: 3 };
+        let mut all = klines(&[1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 7.0]);
+
+        let mut series = Series::new(indicator);
+        series.update(&all[..4], 0);
+        series.update(&all, 4);
+        assert_eq!(series.points(), compute(indicator, &all));
+
+        all[5].close = 1.0;
+        series.update(&all, 5);
+        assert_eq!(series.points(), compute(indicator, &all));
+    }
+
+    #[test]
+    fn test_columns() {
+        let indicators = [
+            Indicator::Bollinger { period: 20, k: 2.0 },
+            Indicator::Rsi { period: 2 },
+        ];
+
+        let columns = columns(
+            &indicators,
+            &klines(&[1.0, 2.0, 3.0]),
+            &Precision::default(),
+        );
+        let names = columns.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec!["bb_20_2_mid", "bb_20_2_upper", "bb_20_2_lower", "rsi_2"]
+        );
+        assert_eq!(columns[3].1, vec!["", "", "100.00"]);
+    }
+}
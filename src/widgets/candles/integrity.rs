@@ -0,0 +1,163 @@
+use crate::sources::binance::Kline;
+
+use super::{
+    bounds::{Bounds, BoundsSet},
+    data::Data,
+    step::Step,
+};
+
+/// Problems found in loaded klines.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IntegrityReport {
+    /// Ranges between loaded klines where klines are missing.
+    pub gaps: BoundsSet,
+    /// Number of klines received more than once and replaced.
+    pub duplicates: usize,
+    /// Open times of klines with inconsistent prices or times.
+    pub inconsistent: Vec<i64>,
+}
+
+impl IntegrityReport {
+    /// Checks klines sorted by open time against the interval step.
+    ///
+    /// Only gaps between klines are reported as data before the first
+    /// and after the last kline may not exist on the exchange.
+    /// Problems outside of bounds are skipped as nobody requested klines there.
+    pub fn check(klines: &[Kline], step: Step, duplicates: usize, bounds: &BoundsSet) -> Self {
+        let mut gaps = vec![];
+        klines.windows(2).for_each(|w| {
+            let expected = step.advance(w[0].t_open, 1);
+            if w[1].t_open > expected {
+                gaps.push(Bounds(expected, w[1].t_open - 1));
+            }
+        });
+
+        let inconsistent = klines
+            .iter()
+            .filter(|k| bounds.contains(k.t_open) && !IntegrityReport::consistent(k))
+            .map(|k| k.t_open)
+            .collect();
+
+        Self {
+            gaps: BoundsSet::new(gaps).intersect(bounds),
+            duplicates,
+            inconsistent,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.gaps.is_empty() && self.duplicates == 0 && self.inconsistent.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "gaps: {}; duplicates: {}; invalid: {}",
+            self.gaps.len(),
+            self.duplicates,
+            self.inconsistent.len()
+        )
+    }
+
+    /// Lists gaps and inconsistent klines with human readable times.
+    pub fn details(&self) -> String {
+        let gaps = self.gaps.vals().into_iter().map(|b| {
+            format!(
+                "gap: {} - {}",
+                Data::format_ts(b.0 as f64),
+                Data::format_ts(b.1 as f64)
+            )
+        });
+        let inconsistent = self
+            .inconsistent
+            .iter()
+            .map(|t| format!("invalid kline: {}", Data::format_ts(*t as f64)));
+
+        gaps.chain(inconsistent).collect::<Vec<_>>().join("\n")
+    }
+
+    fn consistent(k: &Kline) -> bool {
+        k.high >= k.low
+            && (k.low..=k.high).contains(&k.open)
+            && (k.low..=k.high).contains(&k.close)
+            && k.t_close >= k.t_open
+    }
+}
+
+#[cfg(test)]
+mod integrity_tests {
+    use crate::widgets::candles::test_klines::minute;
+
+    use super::*;
+
+    /// Kline of a 60 millis step so gaps are easy to count.
+    fn kline(t_open: i64, low: f64, high: f64) -> Kline {
+        Kline {
+            t_open,
+            t_close: t_open + 59,
+            ..minute(0, low, high, low, high)
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let klines = vec![
+            kline(0, 1.0, 2.0),
+            kline(60, 1.0, 2.0),
+            kline(240, 2.0, 1.0),
+            kline(300, 1.0, 2.0),
+        ];
+
+        let report = IntegrityReport::check(
+            &klines,
+            Step::Millis(60),
+            2,
+            &BoundsSet::new(vec![Bounds(0, 359)]),
+        );
+
+        assert_eq!(report.gaps, BoundsSet::new(vec![Bounds(120, 239)]));
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(report.inconsistent, vec![240]);
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn test_check_outside_bounds() {
+        let klines = vec![
+            kline(0, 1.0, 2.0),
+            kline(240, 2.0, 1.0),
+            kline(300, 1.0, 2.0),
+        ];
+
+        let report = IntegrityReport::check(
+            &klines,
+            Step::Millis(60),
+            0,
+            &BoundsSet::new(vec![Bounds(180, 359)]),
+        );
+
+        assert_eq!(report.gaps, BoundsSet::new(vec![Bounds(180, 239)]));
+        assert_eq!(report.inconsistent, vec![240]);
+
+        let report = IntegrityReport::check(
+            &klines,
+            Step::Millis(60),
+            0,
+            &BoundsSet::new(vec![Bounds(300, 359)]),
+        );
+
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn test_check_ok() {
+        let klines = vec![kline(0, 1.0, 2.0), kline(60, 1.0, 2.0)];
+
+        assert!(IntegrityReport::check(
+            &klines,
+            Step::Millis(60),
+            0,
+            &BoundsSet::new(vec![Bounds(0, 119)])
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, error};
+
+use super::error::CacheError;
+
+/// Directory of json files written atomically through a tmp file and rename.
+#[derive(Debug, Clone)]
+pub struct JsonStore {
+    dir: PathBuf,
+    pretty: bool,
+}
+
+impl JsonStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, pretty: false }
+    }
+
+    /// Writes human readable json, e.g. for files users may edit by hand.
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
+    /// Returns parsed file or None if it does not exist or is unreadable.
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let path = self.path(name);
+        if !path.exists() {
+            debug!("no file found: {}", path.display());
+            return None;
+        }
+
+        let f = match File::open(&path) {
+            Ok(f) => f,
+            Err(err) => {
+                error!("failed to open {}: {err}", path.display());
+                return None;
+            }
+        };
+
+        match serde_json::from_reader(BufReader::new(f)) {
+            Ok(value) => {
+                debug!("loaded: {}", path.display());
+                Some(value)
+            }
+            Err(err) => {
+                error!("failed to parse {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Replaces the file so readers never see it partially written.
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), CacheError> {
+        fs::create_dir_all(&self.dir)?;
+
+        let path = self.path(name);
+        let tmp_path = path.with_extension("tmp");
+        let w = BufWriter::new(File::create(&tmp_path)?);
+        match self.pretty {
+            true => serde_json::to_writer_pretty(w, value)?,
+            false => serde_json::to_writer(w, value)?,
+        }
+        fs::rename(&tmp_path, &path)?;
+
+        debug!("saved: {}", path.display());
+
+        Ok(())
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+}
+
+#[cfg(test)]
+mod json_store_tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let store = JsonStore::new(dir.clone());
+
+        assert_eq!(store.load::<Vec<i64>>("values"), None);
+
+        store.save("values", &vec![1, 2, 3]).unwrap();
+        assert_eq!(store.load::<Vec<i64>>("values"), Some(vec![1, 2, 3]));
+        assert!(!dir.join("values.tmp").exists());
+
+        fs::write(dir.join("broken.json"), "[1, 2").unwrap();
+        assert_eq!(store.load::<Vec<i64>>("broken"), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::sources::binance::{Interval, Kline};
+
+use super::{bounds::BoundsSet, error::CacheError, json_store::JsonStore};
+
+const CACHE_DIR: &str = ".cache/klines";
+
+/// Downloaded klines together with the time range they cover.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub bounds: BoundsSet,
+    pub klines: Vec<Kline>,
+}
+
+/// On disk storage of klines keyed by symbol and interval.
+#[derive(Debug, Clone)]
+pub struct KlineCache {
+    store: JsonStore,
+}
+
+impl Default for KlineCache {
+    fn default() -> Self {
+        Self::new(PathBuf::from(CACHE_DIR))
+    }
+}
+
+impl KlineCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            store: JsonStore::new(dir),
+        }
+    }
+
+    /// Returns cached entry or None if there is nothing cached or cache is unreadable.
+    pub fn load(&self, symbol: &str, interval: Interval) -> Option<CacheEntry> {
+        let entry = self
+            .store
+            .load::<CacheEntry>(&KlineCache::name(symbol, interval))?;
+        debug!("loaded {} klines from cache", entry.klines.len());
+        Some(entry)
+    }
+
+    /// Replaces cached entry for the symbol and interval.
+    pub fn save(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        entry: &CacheEntry,
+    ) -> Result<(), CacheError> {
+        self.store.save(&KlineCache::name(symbol, interval), entry)
+    }
+
+    fn name(symbol: &str, interval: Interval) -> String {
+        // interval debug name is used because binance notation is case sensitive: 1m and 1M
+        format!("{symbol}_{interval:?}")
+    }
+}
+
+#[cfg(test)]
+mod kline_cache_tests {
+    use std::fs;
+
+    use crate::widgets::candles::bounds::Bounds;
+
+    use super::*;
+
+    #[test]
+    fn test_save_load() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let cache = KlineCache::new(dir.clone());
+
+        assert_eq!(cache.load("BTCUSDT", Interval::Minute), None);
+
+        let entry = CacheEntry {
+            bounds: BoundsSet::new(vec![Bounds(0, 120_000)]),
+            klines: vec![
+                Kline {
+                    t_open: 0,
+                    t_close: 59_999,
+                    ..Default::default()
+                },
+                Kline {
+                    t_open: 60_000,
+                    t_close: 119_999,
+                    ..Default::default()
+                },
+            ],
+        };
+        cache.save("BTCUSDT", Interval::Minute, &entry).unwrap();
+
+        assert_eq!(cache.load("BTCUSDT", Interval::Minute), Some(entry));
+        assert_eq!(cache.load("BTCUSDT", Interval::Month), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
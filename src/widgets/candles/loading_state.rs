@@ -3,6 +3,7 @@ use tracing::{debug, info};
 use super::{
     bounds::BoundsSet,
     pages::{Page, Pages},
+    step::Step,
 };
 
 #[derive(Default, Debug, Clone)]
@@ -13,8 +14,8 @@ pub struct LoadingState {
 }
 
 impl LoadingState {
-    pub fn new(bounds: &BoundsSet, step: usize, per_page_limit: usize) -> Option<Self> {
-        debug!("initializing LoadingState: bounds: {bounds:?}; step: {step}; per page limit: {per_page_limit}");
+    pub fn new(bounds: &BoundsSet, step: Step, per_page_limit: usize) -> Option<Self> {
+        debug!("initializing LoadingState: bounds: {bounds:?}; step: {step:?}; per page limit: {per_page_limit}");
 
         Some(Self {
             pages: Pages::new(bounds.clone(), step, per_page_limit)?,
@@ -26,6 +27,10 @@ impl LoadingState {
         self.curr_page.0
     }
 
+    pub fn right_edge(&self) -> i64 {
+        self.curr_page.1
+    }
+
     pub fn get_next_page(&mut self) -> Option<Page> {
         let res = self.pages.next();
         if let Some(p) = res {
@@ -46,7 +51,7 @@ impl LoadingState {
     }
 
     pub fn progress(&mut self) -> f32 {
-        if self.pages.len() == 0 {
+        if self.pages.is_empty() {
             return 1.0;
         }
 
@@ -61,3 +66,103 @@ impl LoadingState {
         self.pages.len()
     }
 }
+
+#[cfg(test)]
+mod loading_state_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crossbeam::channel::unbounded;
+
+    use crate::network::fixtures::{Fault, Fixture, Replayer};
+    use crate::network::rest::{Mode, Rest};
+    use crate::sources::binance::{errors::ClientError, Client, Interval};
+    use crate::sources::AsyncClient;
+    use crate::widgets::candles::bounds::Bounds;
+
+    use super::*;
+
+    const BASE_URL: &str = "http://replay.test";
+    const PATH_KLINE: &str = "/api/v3/klines";
+
+    /// Records page of minute klines the way binance returns them.
+    fn record_page(dir: &std::path::Path, page: &Page, limit: usize) {
+        let klines: Vec<String> = (0..limit as i64)
+            .map(|i| page.0 + i * 60_000)
+            .map(|t| {
+                format!(
+                    r#"[{t},"1.0","2.0","0.5","1.5","10.0",{},"15.0",3,"5.0","7.5","0"]"#,
+                    t + 59_999
+                )
+            })
+            .collect();
+
+        Fixture::new(
+            &format!("{BASE_URL}{PATH_KLINE}"),
+            &[
+                ("symbol", "BTCUSDT"),
+                ("interval", "1m"),
+                ("startTime", &page.0.to_string()),
+                ("limit", &limit.to_string()),
+            ],
+            200,
+            &format!("[{}]", klines.join(",")),
+        )
+        .save(dir)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_replay_faults() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let replayer = Replayer::new(dir.clone())
+            .with_fault(PATH_KLINE, Fault::Timeout, 1)
+            .with_fault(PATH_KLINE, Fault::TooManyRequests(Some(0)), 1)
+            .with_fault(PATH_KLINE, Fault::MalformedJson, 1);
+        let source = Client::new(BASE_URL.to_string())
+            .with_rest(Rest::new().with_mode(Mode::Replay(Arc::new(replayer))));
+        let client = AsyncClient::new(Arc::new(source), 1);
+        let (s, r) = unbounded();
+
+        let bounds = BoundsSet::new(vec![Bounds(0, 4 * 60_000)]);
+        let mut state = LoadingState::new(&bounds, Step::from(Interval::Minute), 2).unwrap();
+        assert_eq!(state.pages(), 2);
+
+        let download = client.start_download();
+        let request = |state: &LoadingState| {
+            client.kline(
+                &download,
+                "BTCUSDT".to_string(),
+                Interval::Minute,
+                state.left_edge(),
+                state.page_size(),
+                s.clone(),
+            );
+            r.recv_timeout(Duration::from_secs(10)).unwrap().result
+        };
+
+        // timeout and 429 are retried, malformed body is not so the page fails
+        let page = state.get_next_page().unwrap();
+        record_page(&dir, &page, state.page_size());
+        assert!(matches!(
+            request(&state),
+            Err(ClientError::Serialization(_))
+        ));
+        assert_eq!(state.progress(), 0.0);
+
+        let klines = request(&state).unwrap();
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].t_open, page.0);
+        state.inc_loaded_pages(1);
+        assert_eq!(state.progress(), 0.5);
+
+        let page = state.get_next_page().unwrap();
+        record_page(&dir, &page, state.page_size());
+        assert_eq!(request(&state).unwrap()[1].t_open, page.0 + 60_000);
+        state.inc_loaded_pages(1);
+        assert_eq!(state.progress(), 1.0);
+        assert!(state.get_next_page().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,17 +1,45 @@
+mod annotations;
+mod annotations_store;
 mod bounds;
 mod candles_drawer;
+mod chart_type;
+mod compare_drawer;
+mod compare_props;
 mod data;
+mod depth_drawer;
+mod depth_props;
 mod error;
+mod export;
+mod import;
+mod indicators;
+mod integrity;
+mod json_store;
+mod kline_cache;
 mod loading_state;
 mod pages;
+mod precision;
 mod props;
+mod resample;
 mod state;
+mod step;
 mod symbols;
+mod symbols_cache;
+#[cfg(test)]
+mod test_klines;
 mod time_input;
 mod time_range;
 mod time_range_settings;
+mod trades_data;
+mod trades_drawer;
+mod trades_props;
 
+pub use self::bounds::{Bounds, BoundsSet};
+pub use self::compare_props::CompareProps;
+pub use self::import::export_symbol;
+pub use self::pages::{Page, Pages};
 pub use self::props::Props;
+pub use self::step::Step;
 pub use self::symbols::Symbols;
 pub use self::time_input::TimeInput;
 pub use self::time_range::TimeRange;
+pub use self::trades_props::TradesProps;
@@ -1,6 +1,6 @@
 use tracing::{debug, error};
 
-use super::bounds::BoundsSet;
+use super::{bounds::BoundsSet, step::Step};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Page(pub i64, pub i64);
@@ -9,7 +9,7 @@ pub struct Page(pub i64, pub i64);
 pub struct Pages {
     curr_page_idx: usize,
     vals: Vec<Page>,
-    step: usize,
+    step: Step,
 }
 
 impl Pages {
@@ -17,10 +17,12 @@ impl Pages {
     ///
     /// Page is a pair of start and end
     /// where the start is included in the range and the end is not.
-    pub fn new(bounds: BoundsSet, step: usize, limit: usize) -> Option<Self> {
-        debug!("initializing new pages; bounds: {bounds:?}; step: {step}; limit: {limit}");
+    /// Pages are split by calendar aware step, so every page holds
+    /// at most limit klines even for intervals with variable length.
+    pub fn new(bounds: BoundsSet, step: Step, limit: usize) -> Option<Self> {
+        debug!("initializing new pages; bounds: {bounds:?}; step: {step:?}; limit: {limit}");
 
-        if step < 1 {
+        if !step.valid() {
             error!("invalid step. Step must be greater than 0");
 
             return None;
@@ -28,18 +30,18 @@ impl Pages {
 
         let mut vals = vec![];
         bounds.vals().iter_mut().for_each(|b| {
-            if b.len() <= limit * step as usize {
-                debug!("not splitting bounds to pages; bounds: {b:?}; step: {step}");
+            if step.count(b.0, b.1) <= limit {
+                debug!("not splitting bounds to pages; bounds: {b:?}; step: {step:?}");
 
                 vals.push(Page(b.0, b.1));
                 return;
             }
 
-            debug!("iterating inside bounds constructing pages: bounds: {b:?}; step: {step}");
+            debug!("iterating inside bounds constructing pages: bounds: {b:?}; step: {step:?}");
 
             let mut page_start = b.0;
             loop {
-                let mut page_end = page_start + (step * limit) as i64;
+                let mut page_end = step.advance(page_start, limit as i64);
                 if page_end > b.1 {
                     page_end = b.1;
                 }
@@ -61,11 +63,24 @@ impl Pages {
         })
     }
 
+    /// Number of all pages including already iterated ones.
     pub fn len(&self) -> usize {
         self.vals.len()
     }
 
-    pub fn next(&mut self) -> Option<Page> {
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
+    pub fn page_size(&self, page: Page) -> usize {
+        self.step.count(page.0, page.1)
+    }
+}
+
+impl Iterator for Pages {
+    type Item = Page;
+
+    fn next(&mut self) -> Option<Page> {
         if let Some(page) = self.vals.get(self.curr_page_idx) {
             self.curr_page_idx += 1;
             return Some(page.clone());
@@ -73,40 +88,113 @@ impl Pages {
 
         None
     }
-
-    pub fn page_size(&self, page: Page) -> usize {
-        ((page.1 - page.0) / self.step as i64) as usize
-    }
 }
 
 #[cfg(test)]
 mod pages_tests {
+    use chrono::NaiveDate;
+
     use crate::widgets::candles::bounds::Bounds;
 
     use super::*;
 
     #[test]
     fn test_pages_new() {
-        let pages_res = Pages::new(BoundsSet::new(vec![Bounds(0, 50), Bounds(60, 150)]), 1, 50);
+        let pages_res = Pages::new(
+            BoundsSet::new(vec![Bounds(0, 50), Bounds(60, 150)]),
+            Step::Millis(1),
+            50,
+        );
         assert_ne!(pages_res, None);
         assert_eq!(
             pages_res.unwrap(),
             Pages {
                 vals: vec![Page(0, 50), Page(60, 110), Page(110, 150)],
-                step: 1,
+                step: Step::Millis(1),
                 ..Default::default()
             }
         );
 
-        let pages_res = Pages::new(BoundsSet::new(vec![Bounds(0, 50), Bounds(60, 150)]), 2, 25);
+        let pages_res = Pages::new(
+            BoundsSet::new(vec![Bounds(0, 50), Bounds(60, 150)]),
+            Step::Millis(2),
+            25,
+        );
         assert_ne!(pages_res, None);
         assert_eq!(
             pages_res.unwrap(),
             Pages {
                 vals: vec![Page(0, 50), Page(60, 110), Page(110, 150)],
-                step: 2,
+                step: Step::Millis(2),
                 ..Default::default()
             }
         );
     }
+
+    #[test]
+    fn test_pages_new_months() {
+        let ts = |y, m| {
+            NaiveDate::from_ymd(y, m, 1)
+                .and_hms(0, 0, 0)
+                .timestamp_millis()
+        };
+
+        let pages_res = Pages::new(
+            BoundsSet::new(vec![Bounds(ts(2021, 11), ts(2022, 4))]),
+            Step::Months(1),
+            2,
+        );
+        assert_ne!(pages_res, None);
+
+        let pages = pages_res.unwrap();
+        assert_eq!(
+            pages,
+            Pages {
+                vals: vec![
+                    Page(ts(2021, 11), ts(2022, 1)),
+                    Page(ts(2022, 1), ts(2022, 3)),
+                    Page(ts(2022, 3), ts(2022, 4)),
+                ],
+                step: Step::Months(1),
+                ..Default::default()
+            }
+        );
+        assert_eq!(pages.page_size(Page(ts(2022, 3), ts(2022, 4))), 1);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut pages =
+            Pages::new(BoundsSet::new(vec![Bounds(0, 120)]), Step::Millis(1), 50).unwrap();
+        assert_eq!(pages.next(), Some(Page(0, 50)));
+        assert_eq!(
+            pages.clone().collect::<Vec<_>>(),
+            vec![Page(50, 100), Page(100, 120)]
+        );
+        assert_eq!(pages.len(), 3);
+        assert!(!pages.is_empty());
+
+        assert!(Pages::new(BoundsSet::default(), Step::Millis(1), 50)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_page_size_unaligned() {
+        let minute = 60_000;
+        let pages = Pages::new(
+            BoundsSet::new(vec![Bounds(0, 2 * minute + 30_000)]),
+            Step::Millis(minute),
+            2,
+        )
+        .unwrap();
+
+        // klines open at 0, 1m and 2m, the last page still has to request the one at 2m
+        assert_eq!(
+            pages.vals,
+            vec![Page(0, 2 * minute), Page(2 * minute, 2 * minute + 30_000)]
+        );
+        assert_eq!(pages.page_size(Page(0, 2 * minute)), 2);
+        assert_eq!(pages.page_size(Page(2 * minute, 2 * minute + 30_000)), 1);
+    }
 }
@@ -0,0 +1,93 @@
+use serde::Serialize;
+
+use crate::sources::binance::{Kline, Symbol};
+
+const DEFAULT_PRECISION: usize = 8;
+
+/// Number of decimal places used to show prices and quantities of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    pub price: usize,
+    pub qty: usize,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self {
+            price: DEFAULT_PRECISION,
+            qty: DEFAULT_PRECISION,
+        }
+    }
+}
+
+impl From<&Symbol> for Precision {
+    fn from(s: &Symbol) -> Self {
+        Self {
+            price: s.quote_asset_precision(),
+            qty: s.base_asset_precision(),
+        }
+    }
+}
+
+impl Precision {
+    pub fn price(&self, v: f64) -> String {
+        format!("{v:.*}", self.price)
+    }
+
+    pub fn qty(&self, v: f64) -> String {
+        format!("{v:.*}", self.qty)
+    }
+
+    /// Formats kline for export keeping column names of the kline.
+    pub fn kline_row(&self, k: &Kline) -> KlineRow {
+        KlineRow {
+            t_open: k.t_open,
+            open: self.price(k.open),
+            high: self.price(k.high),
+            low: self.price(k.low),
+            close: self.price(k.close),
+            volume: self.qty(k.volume),
+            t_close: k.t_close,
+            quote_asset_volume: self.price(k.quote_asset_volume),
+            number_of_trades: k.number_of_trades,
+            taker_buy_base_asset_volume: self.qty(k.taker_buy_base_asset_volume),
+            taker_buy_quote_asset_volume: self.price(k.taker_buy_quote_asset_volume),
+        }
+    }
+}
+
+/// Kline with values formatted with the symbol precision.
+#[derive(Debug, Serialize)]
+pub struct KlineRow {
+    pub t_open: i64,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: String,
+    pub t_close: i64,
+    pub quote_asset_volume: String,
+    pub number_of_trades: i64,
+    pub taker_buy_base_asset_volume: String,
+    pub taker_buy_quote_asset_volume: String,
+}
+
+#[cfg(test)]
+mod precision_tests {
+    use super::*;
+
+    #[test]
+    fn test_kline_row() {
+        let precision = Precision { price: 8, qty: 2 };
+        let k = Kline {
+            open: 0.00001234,
+            volume: 1234567.891,
+            ..Default::default()
+        };
+
+        let row = precision.kline_row(&k);
+
+        assert_eq!(row.open, "0.00001234");
+        assert_eq!(row.volume, "1234567.89");
+    }
+}
@@ -1,53 +1,129 @@
-use std::fs::File;
-use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Duration;
 
-use chrono::{Date, NaiveDateTime, Utc};
+use chrono::{Date, DateTime, NaiveDateTime, Utc};
 use crossbeam::channel::{unbounded, Receiver, Sender};
-use egui::{CentralPanel, ProgressBar, TopBottomPanel, Ui};
+use egui::{CentralPanel, Color32, ComboBox, ProgressBar, RichText, TopBottomPanel, Ui};
 use egui_notify::{Anchor, Toasts};
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
 use crate::netstrat::{Drawer, ThreadPool};
-use crate::sources::binance::{Client, Kline};
+use crate::sources::binance::{
+    errors::ClientError, Client, FundingRate, Interval, Kline, KlineStream, Market, OpenInterest,
+    PriceKind, StreamEvent, Symbol, FUNDING_PERIOD_MILLIS, OPEN_INTEREST_HISTORY_MILLIS,
+    OPEN_INTEREST_PERIODS, STREAM_URL,
+};
+use crate::sources::{clock, AsyncClient, Download, MarketDataSource, Tagged};
 use crate::widgets::candles::bounds::BoundsSet;
-use crate::widgets::AppWidget;
+use crate::widgets::{AppWidget, OpenDropFile};
 
+use super::annotations_store::AnnotationsStore;
 use super::bounds::Bounds;
 use super::candles_drawer::CandlesDrawer;
-use super::error::CandlesError;
+use super::chart_type::ChartType;
+use super::depth_props::DepthProps;
+use super::error::ImportError;
+use super::export::{write_csv, write_csv_with_columns};
+use super::import::{export_symbol, import_file, merge_into_cache, ImportedKlines};
+use super::indicators::{self, Indicator};
+use super::integrity::IntegrityReport;
+use super::kline_cache::{CacheEntry, KlineCache};
+use super::pages::Pages;
+use super::precision::{KlineRow, Precision};
+use super::resample::resample;
 use super::state::State;
+use super::step::Step;
 use super::time_range_settings::TimeRangeSettings;
 use super::TimeRange;
 
 const THREAD_POOL_SIZE: usize = 15;
+const MAX_CONCURRENT_PAGES: usize = 15;
+const FUNDING_LIMIT: usize = 1000;
+const OPEN_INTEREST_LIMIT: usize = 500;
+const IMPORT_HINT: &str =
+    "Drop binance data dump .zip or .csv or exported .csv files here or click to open a file dialog";
 
 #[derive(Default)]
 struct ExportState {
     triggered: bool,
 }
 
+/// Source and live stream of a market symbols can be shown from.
+#[derive(Clone)]
+struct MarketFeed {
+    market: Market,
+    source: Arc<dyn MarketDataSource>,
+    stream_url: String,
+}
+
+#[derive(Default)]
+struct LiveState {
+    enabled: bool,
+    stop: Option<Arc<AtomicBool>>,
+    events_sub: Option<Receiver<StreamEvent>>,
+    /// Stream has delivered klines since it was started.
+    streamed: bool,
+    /// Stream connected while download was in progress so backfill waits for it.
+    backfill_pending: bool,
+}
+
 pub struct Props {
     time_range: Box<dyn AppWidget>,
 
     candles: CandlesDrawer,
+    depth: DepthProps,
+    show_depth: bool,
+    chart_type: ChartType,
+    show_volume: bool,
+    show_taker_buy: bool,
+    indicators: Vec<Indicator>,
     symbol: String,
+    /// Market of the shown symbol.
+    market: Market,
+    price_kind: PriceKind,
+    /// Funding events of the shown perpetual contract sorted by time.
+    funding: Vec<FundingRate>,
+    /// Open interest statistics of the shown futures contract sorted by time.
+    open_interest: Vec<OpenInterest>,
+    precision: Precision,
 
     max_frame_pages: usize,
     data_changed: bool,
+    download_failed: bool,
+    /// Shown klines are an opened export which must not leak into the cache.
+    read_only: bool,
+    integrity: IntegrityReport,
     state: State,
     export_state: ExportState,
+    live_state: LiveState,
+    cache: KlineCache,
+    annotations_store: AnnotationsStore,
+    import: OpenDropFile,
 
     toasts: Toasts,
 
     pool: ThreadPool,
-
-    klines_pub: Sender<Result<Vec<Kline>, CandlesError>>,
-    klines_sub: Receiver<Result<Vec<Kline>, CandlesError>>,
+    source: Arc<dyn MarketDataSource>,
+    client: AsyncClient,
+    /// Download pages of which are currently shown. Pages of other downloads are dropped.
+    download: Option<Download>,
+    stream_url: String,
+    feeds: Vec<MarketFeed>,
+
+    klines_pub: Sender<Tagged<Result<Vec<Kline>, ClientError>>>,
+    klines_sub: Receiver<Tagged<Result<Vec<Kline>, ClientError>>>,
+    funding_pub: Sender<Tagged<Result<Vec<FundingRate>, ClientError>>>,
+    funding_sub: Receiver<Tagged<Result<Vec<FundingRate>, ClientError>>>,
+    open_interest_pub: Sender<Tagged<Result<Vec<OpenInterest>, ClientError>>>,
+    open_interest_sub: Receiver<Tagged<Result<Vec<OpenInterest>, ClientError>>>,
+    imported_pub: Sender<Result<ImportedKlines, ImportError>>,
+    imported_sub: Receiver<Result<ImportedKlines, ImportError>>,
     drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
     symbol_pub: Sender<String>,
-    symbol_sub: Receiver<String>,
+    symbol_sub: Receiver<Symbol>,
     props_pub: Sender<TimeRangeSettings>,
     props_sub: Receiver<TimeRangeSettings>,
     export_sub: Receiver<TimeRangeSettings>,
@@ -64,11 +140,15 @@ impl Default for Props {
         let (s_props1, r_props1) = unbounded();
         let (s_export, r_export) = unbounded();
         let (s_klines, r_klines) = unbounded();
+        let (s_funding, r_funding) = unbounded();
+        let (s_open_interest, r_open_interest) = unbounded();
+        let (s_imported, r_imported) = unbounded();
         let (s_bounds, r_bounds) = unbounded();
         let (s_drawer, _) = unbounded();
+        let (_, r_symbol) = unbounded();
 
         let time_range_chooser = Box::new(TimeRange::new(
-            r_symbols.clone(),
+            r_symbols,
             s_props,
             r_props1,
             s_export,
@@ -78,6 +158,9 @@ impl Default for Props {
         let candles = CandlesDrawer::new(s_bounds);
 
         let pool = ThreadPool::new(THREAD_POOL_SIZE);
+        let source: Arc<dyn MarketDataSource> = Arc::new(Client::default());
+        let depth = DepthProps::new(source.clone());
+        let client = AsyncClient::new(source.clone(), MAX_CONCURRENT_PAGES);
 
         Self {
             max_frame_pages,
@@ -85,12 +168,21 @@ impl Default for Props {
             time_range: time_range_chooser,
 
             candles,
+            depth,
 
             pool,
+            feeds: vec![MarketFeed {
+                market: Market::Spot,
+                source: source.clone(),
+                stream_url: STREAM_URL.to_string(),
+            }],
+            source,
+            client,
+            stream_url: STREAM_URL.to_string(),
 
             toasts,
 
-            symbol_sub: r_symbols,
+            symbol_sub: r_symbol,
             symbol_pub: s_symbols,
             drawer_pub: s_drawer,
             props_sub: r_props,
@@ -99,51 +191,310 @@ impl Default for Props {
             drag_sub: r_bounds,
             klines_sub: r_klines,
             klines_pub: s_klines,
-
+            funding_sub: r_funding,
+            funding_pub: s_funding,
+            open_interest_sub: r_open_interest,
+            open_interest_pub: s_open_interest,
+            imported_sub: r_imported,
+            imported_pub: s_imported,
+            import: OpenDropFile::new(IMPORT_HINT, "Klines", &["zip", "csv"]).multiple(),
+
+            download: Default::default(),
             data_changed: Default::default(),
+            download_failed: Default::default(),
+            read_only: Default::default(),
+            integrity: Default::default(),
+            show_depth: Default::default(),
+            chart_type: Default::default(),
+            show_volume: true,
+            show_taker_buy: Default::default(),
+            indicators: Default::default(),
+            cache: Default::default(),
+            annotations_store: Default::default(),
             symbol: Default::default(),
+            market: Default::default(),
+            price_kind: Default::default(),
+            funding: Default::default(),
+            open_interest: Default::default(),
+            precision: Default::default(),
             state: Default::default(),
             export_state: Default::default(),
+            live_state: Default::default(),
         }
     }
 }
 
 impl Props {
     pub fn new(
-        symbol_sub: Receiver<String>,
+        source: Arc<dyn MarketDataSource>,
+        stream_url: String,
+        symbol_sub: Receiver<Symbol>,
         drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
     ) -> Self {
         info!("initing widget graph");
         Self {
+            depth: DepthProps::new(source.clone()),
+            client: AsyncClient::new(source.clone(), MAX_CONCURRENT_PAGES),
+            feeds: vec![MarketFeed {
+                market: Market::Spot,
+                source: source.clone(),
+                stream_url: stream_url.clone(),
+            }],
+            source,
+            stream_url,
             symbol_sub,
             drawer_pub,
             ..Default::default()
         }
     }
 
-    fn update_data(&mut self, klines: &mut Vec<Kline>) {
+    /// Lets symbols of futures market be shown using the source and live stream.
+    pub fn with_futures(mut self, source: Arc<dyn MarketDataSource>, stream_url: String) -> Self {
+        self.feeds.push(MarketFeed {
+            market: Market::UsdFutures,
+            source,
+            stream_url,
+        });
+        self
+    }
+
+    /// Switches source and stream to the market of the next shown symbol.
+    fn set_market(&mut self, market: Market) {
+        let feed = match self.feeds.iter().find(|f| f.market == market) {
+            Some(feed) => feed.clone(),
+            None => {
+                error!("no source for market: {}", market.name());
+                return;
+            }
+        };
+
+        info!("setting market: {}", market.name());
+
+        self.stop_stream();
+        self.cancel_download();
+
+        self.market = market;
+        self.price_kind = PriceKind::Last;
+        self.client = self.client.with_source(feed.source.clone());
+        self.depth = DepthProps::new(feed.source.clone());
+        self.source = feed.source;
+        self.stream_url = feed.stream_url;
+    }
+
+    /// Name klines are cached under. Spot symbols are cached by plain names.
+    fn cache_symbol(&self) -> String {
+        export_symbol(self.market, self.price_kind, &self.symbol)
+    }
+
+    /// Requests funding events of the shown perpetual contract inside the bounds.
+    fn request_funding(&mut self, bounds: &BoundsSet) {
+        if self.market != Market::UsdFutures {
+            return;
+        }
+
+        let pages = match Pages::new(
+            bounds.clip_right(clock::now_millis()),
+            Step::Millis(FUNDING_PERIOD_MILLIS),
+            FUNDING_LIMIT,
+        ) {
+            Some(pages) => pages,
+            None => {
+                error!("failed to split funding requests to pages");
+                return;
+            }
+        };
+
+        let download = self
+            .download
+            .get_or_insert_with(|| self.client.start_download());
+        for page in pages {
+            debug!(
+                "requesting funding: symbol: {}, page: {page:?}",
+                self.symbol
+            );
+
+            let symbol = self.symbol.clone();
+            self.client
+                .request(download, self.funding_pub.clone(), move |source| {
+                    source.funding_rate_async(symbol.clone(), page.0, page.1 - 1, FUNDING_LIMIT)
+                });
+        }
+    }
+
+    fn handle_funding(&mut self) {
+        let results: Vec<Tagged<Result<Vec<FundingRate>, ClientError>>> =
+            self.funding_sub.try_iter().collect();
+        if results.is_empty() {
+            return;
+        }
+
+        let download_id = self.download.as_ref().map(|d| d.id());
+        results.into_iter().for_each(|tagged| {
+            if Some(tagged.request_id) != download_id {
+                debug!("dropping stale funding of download: {}", tagged.request_id);
+                return;
+            }
+
+            match tagged.result {
+                Ok(mut rates) => self.funding.append(&mut rates),
+                Err(err) => {
+                    error!("failed to get funding rates: {err}");
+                    self.toasts.error("Failed to get funding rates");
+                }
+            }
+        });
+
+        self.funding.sort_by_key(|f| f.funding_time);
+        self.funding.dedup_by_key(|f| f.funding_time);
+        self.candles.set_funding(self.funding.clone());
+        self.data_changed = true;
+    }
+
+    /// Requests open interest statistics of the shown futures contract inside the bounds.
+    ///
+    /// Statistics are kept only for the last days and only for some periods, so other
+    /// bounds and intervals are skipped.
+    fn request_open_interest(&mut self, bounds: &BoundsSet, interval: Interval) {
+        if self.market != Market::UsdFutures || !OPEN_INTEREST_PERIODS.contains(&interval) {
+            return;
+        }
+
+        let now = clock::now_millis();
+        let kept = BoundsSet::new(vec![Bounds(now - OPEN_INTEREST_HISTORY_MILLIS, now)]);
+        let pages = match Pages::new(
+            bounds.intersect(&kept),
+            Step::from(interval),
+            OPEN_INTEREST_LIMIT,
+        ) {
+            Some(pages) => pages,
+            None => {
+                debug!("no open interest kept inside bounds: {bounds:?}");
+                return;
+            }
+        };
+
+        let download = self
+            .download
+            .get_or_insert_with(|| self.client.start_download());
+        for page in pages {
+            debug!(
+                "requesting open interest: symbol: {}, page: {page:?}",
+                self.symbol
+            );
+
+            let symbol = self.symbol.clone();
+            self.client
+                .request(download, self.open_interest_pub.clone(), move |source| {
+                    source.open_interest_hist_async(
+                        symbol.clone(),
+                        interval,
+                        page.0,
+                        page.1 - 1,
+                        OPEN_INTEREST_LIMIT,
+                    )
+                });
+        }
+    }
+
+    fn handle_open_interest(&mut self) {
+        let results: Vec<Tagged<Result<Vec<OpenInterest>, ClientError>>> =
+            self.open_interest_sub.try_iter().collect();
+        if results.is_empty() {
+            return;
+        }
+
+        let download_id = self.download.as_ref().map(|d| d.id());
+        results.into_iter().for_each(|tagged| {
+            if Some(tagged.request_id) != download_id {
+                debug!(
+                    "dropping stale open interest of download: {}",
+                    tagged.request_id
+                );
+                return;
+            }
+
+            match tagged.result {
+                Ok(mut stats) => self.open_interest.append(&mut stats),
+                Err(err) => {
+                    error!("failed to get open interest: {err}");
+                    self.toasts.error("Failed to get open interest");
+                }
+            }
+        });
+
+        self.open_interest.sort_by_key(|oi| oi.time);
+        self.open_interest.dedup_by_key(|oi| oi.time);
+        self.candles.set_open_interest(&self.open_interest);
+        self.data_changed = true;
+    }
+
+    /// Adds klines to the chart returning number of replaced klines.
+    fn update_data(&mut self, klines: &mut Vec<Kline>) -> usize {
         info!(
             "adding {} entries to volume and candles widgets",
             klines.len()
         );
 
-        self.candles.add_data(klines);
+        let replaced = self.candles.add_data(klines);
+        if self.chart_type.pick_brick(&self.candles.data().vals) {
+            self.candles.set_chart_type(self.chart_type);
+        }
 
         self.data_changed = true;
+
+        replaced
+    }
+
+    /// Validates loaded klines keeping duplicates counted while receiving pages.
+    fn check_integrity(&mut self, interval: Interval) {
+        self.integrity = IntegrityReport::check(
+            &self.candles.data().vals,
+            Step::from(interval),
+            self.integrity.duplicates,
+            &self.state.props.bounds,
+        );
+
+        if !self.integrity.is_ok() {
+            warn!(
+                "klines integrity check failed: {}",
+                self.integrity.summary()
+            );
+        }
+    }
+
+    /// Downloads again only bounds missing in the loaded klines.
+    fn reload_gaps(&mut self) {
+        info!("reloading gaps: {:?}", self.integrity.gaps);
+
+        self.state.reload(&self.integrity.gaps);
+        self.download_failed = false;
+        self.perform_data_request();
     }
 
     fn start_download(&mut self, props: TimeRangeSettings, reset_state: bool) {
         if reset_state {
-            self.state = State::default();
+            self.cancel_download();
+            self.reset_state(props.interval);
         }
 
+        self.candles.set_display_interval(props.resample_interval());
+
         self.state.apply_props(&props);
+        self.request_funding(&props.bounds);
+        self.request_open_interest(&props.bounds, props.interval);
+
+        if reset_state {
+            self.start_stream();
+        }
 
         if self.state.loading.pages() == 0 {
             debug!("data already downloaded, skipping download");
             return;
         }
 
+        self.download_failed = false;
+        self.integrity.duplicates = 0;
+
         debug!(
             "data splitted in {} pages; starting download...",
             self.state.loading.pages()
@@ -152,31 +503,241 @@ impl Props {
         self.perform_data_request();
     }
 
+    /// Replaces drawn annotations with the ones saved for the current symbol.
+    fn load_annotations(&mut self) {
+        let items = self.annotations_store.load(self.market, &self.symbol);
+        debug!("loaded {} annotations for {}", items.len(), self.symbol);
+
+        match self.candles.annotations().lock() {
+            Ok(mut annotations) => annotations.set_items(items),
+            Err(err) => error!("failed to lock annotations: {err}"),
+        }
+    }
+
+    /// Saves annotations of the current symbol if they were changed on the plot.
+    fn save_annotations(&mut self) {
+        let annotations = self.candles.annotations();
+        let mut annotations = match annotations.lock() {
+            Ok(annotations) => annotations,
+            Err(err) => {
+                error!("failed to lock annotations: {err}");
+                return;
+            }
+        };
+        if !annotations.take_changed() || self.symbol.is_empty() {
+            return;
+        }
+
+        if let Err(err) =
+            self.annotations_store
+                .save(self.market, &self.symbol, annotations.items())
+        {
+            error!("failed to save annotations: {err}");
+            self.toasts.error(format!("Failed to save drawings: {err}"));
+        }
+    }
+
+    /// Drops renko brick of the previous symbol so it is picked from prices of the new one.
+    fn reset_brick(&mut self) {
+        self.chart_type.reset_brick();
+        self.candles.set_chart_type(self.chart_type);
+    }
+
+    /// Clears loaded data and restores data cached for the symbol and interval.
+    fn reset_state(&mut self, interval: Interval) {
+        self.candles.clear();
+        self.read_only = false;
+        self.state = State::default();
+        self.integrity = IntegrityReport::default();
+        self.funding.clear();
+        self.open_interest.clear();
+
+        if let Some(mut entry) = self.cache.load(&self.cache_symbol(), interval) {
+            info!(
+                "restored {} klines from cache; covered bounds: {:?}",
+                entry.klines.len(),
+                entry.bounds
+            );
+
+            self.state = State::new(entry.bounds);
+            if !entry.klines.is_empty() {
+                self.update_data(&mut entry.klines);
+                self.check_integrity(interval);
+            }
+        }
+    }
+
+    /// Subscribes to live klines for the current symbol and interval if live mode is enabled.
+    fn start_stream(&mut self) {
+        self.stop_stream();
+
+        if !self.live_state.enabled || self.symbol.is_empty() {
+            return;
+        }
+
+        if self.price_kind != PriceKind::Last {
+            warn!(
+                "live stream is not available for {}",
+                self.price_kind.name()
+            );
+            return;
+        }
+
+        let stream = KlineStream::new(
+            &self.stream_url,
+            self.symbol.clone(),
+            self.state.props.interval,
+        );
+
+        let (events_pub, events_sub) = unbounded();
+        self.live_state.stop = Some(stream.stop_handle());
+        self.live_state.events_sub = Some(events_sub);
+        self.live_state.streamed = false;
+        self.live_state.backfill_pending = false;
+
+        thread::spawn(move || stream.run(events_pub));
+    }
+
+    fn stop_stream(&mut self) {
+        if let Some(stop) = self.live_state.stop.take() {
+            debug!("stopping live stream");
+            stop.store(true, Ordering::Relaxed);
+        }
+
+        self.live_state.events_sub = None;
+        self.live_state.backfill_pending = false;
+    }
+
+    /// Downloads klines between the last loaded one and now which the stream does not resend,
+    /// e.g. formed while it was reconnecting.
+    fn backfill_stream(&mut self) {
+        if self.state.loading.progress() < 1.0 {
+            debug!("download in progress, postponing stream backfill");
+            self.live_state.backfill_pending = true;
+            return;
+        }
+        self.live_state.backfill_pending = false;
+
+        let now = clock::now_millis();
+        let live_edge = self.state.props.interval.open_time(now);
+        let reaches_live_edge = matches!(
+            self.state.props.bounds.vals().last(),
+            Some(b) if b.1 >= live_edge
+        );
+        // ranges in the past are not extended to now unless stream has already added klines to them
+        if !reaches_live_edge && !self.live_state.streamed {
+            return;
+        }
+
+        let from = match self.candles.data().vals.last() {
+            Some(k) => k.t_open,
+            None => return,
+        };
+        if from >= now {
+            return;
+        }
+
+        info!("backfilling stream from: {from}");
+        self.state.reload(&BoundsSet::new(vec![Bounds(from, now)]));
+        self.state.add_loaded(Bounds(from, now));
+        self.download_failed = false;
+        self.perform_data_request();
+    }
+
+    fn handle_stream_events(&mut self) {
+        let events: Vec<StreamEvent> = match &self.live_state.events_sub {
+            Some(sub) => sub.try_iter().collect(),
+            None => return,
+        };
+
+        let mut klines = vec![];
+        let mut connected = false;
+        events.into_iter().for_each(|event| match event {
+            StreamEvent::Connected => {
+                info!("live stream connected");
+                connected = true;
+            }
+            StreamEvent::Disconnected(reason) => {
+                warn!("live stream disconnected: {reason}");
+                self.toasts
+                    .warning("Live stream disconnected, reconnecting...");
+            }
+            StreamEvent::Klines(mut vals) => klines.append(&mut vals),
+        });
+
+        if !klines.is_empty() {
+            // the last streamed kline is still open so data is loaded only up to the live edge
+            let t_start = klines.iter().map(|k| k.t_open).min().unwrap_or_default();
+            self.state.add_loaded(Bounds(t_start, clock::now_millis()));
+            self.update_data(&mut klines);
+            self.live_state.streamed = true;
+        }
+
+        if connected {
+            self.backfill_stream();
+        }
+    }
+
+    fn save_cache(&mut self) {
+        if self.download_failed {
+            debug!("download has failed pages, skipping saving to cache");
+            return;
+        }
+
+        if self.read_only {
+            debug!("showing opened export, skipping saving to cache");
+            return;
+        }
+
+        let cache = self.cache.clone();
+        let symbol = self.cache_symbol();
+        let interval = self.state.props.interval;
+
+        // the last kline is still forming, only closed ones are cached so it is downloaded again
+        let now = clock::now_millis();
+        let entry = CacheEntry {
+            bounds: self.state.bounds().clip_right(interval.open_time(now)),
+            klines: self
+                .candles
+                .get_ordered_data()
+                .vals
+                .into_iter()
+                .filter(|k| k.t_close < now)
+                .collect(),
+        };
+        self.pool.execute(move || {
+            if let Err(err) = cache.save(&symbol, interval, &entry) {
+                error!("failed to save klines to cache: {err}");
+            }
+        });
+    }
+
+    /// Cancels pending pages of the current download so they never reach the chart.
+    fn cancel_download(&mut self) {
+        if let Some(download) = self.download.take() {
+            download.cancel();
+        }
+    }
+
     fn perform_data_request(&mut self) {
+        let download = self
+            .download
+            .get_or_insert_with(|| self.client.start_download());
+
         while self.state.loading.get_next_page().is_some() {
             let start_time = self.state.loading.left_edge();
             let interval = self.state.props.interval;
             let limit = self.state.loading.page_size();
             let symbol = self.symbol.to_string();
-
-            let sender = Mutex::new(self.klines_pub.clone());
-            self.pool.execute(move || {
-                debug!("executing klines request: symbol: {symbol}, t_start: {start_time}, limit: {limit}");
-                let data = Client::kline(symbol, interval, start_time, limit);
-                    let res = match data {
-                        Ok(payload) => {
-                            Ok(payload)
-                        }
-                        Err(err) => {
-                            error!("got klines result with error: {err}");
-                            Err(CandlesError::Error)
-                        }
-                    };
-
-                    let send_res = sender.lock().unwrap().send(res);
-                    if let Err(err) = send_res {
-                        error!("failed to send klines to channel: {err}");
-                    };
+            let kind = self.price_kind;
+
+            debug!(
+                "requesting klines: download: {}, symbol: {symbol}, kind: {kind:?}, t_start: {start_time}, limit: {limit}",
+                download.id()
+            );
+            self.client
+                .request(download, self.klines_pub.clone(), move |source| {
+                    source.price_kline_async(symbol.clone(), interval, kind, start_time, limit)
                 });
         }
     }
@@ -184,44 +745,208 @@ impl Props {
     fn export_data(&mut self) {
         debug!("exporting data");
 
+        // klines and indicators are exported the way the chart shows them
+        let resample_interval = self.state.props.resample_interval();
+        let interval = resample_interval.unwrap_or(self.state.props.interval);
+
+        // market and price kind are kept in the name so opened export is not taken for spot
         let name = format!(
-            "{}_{}_{}_{:?}.csv",
-            self.symbol,
+            "{}_{}_{}_{:?}",
+            self.cache_symbol(),
             self.state.props.start_time().timestamp(),
             self.state.props.end_time().timestamp(),
-            self.state.props.interval,
+            interval,
         );
 
-        let path = Path::new(&name);
-        let f_res = File::create(path);
-        if let Err(err) = f_res {
-            error!("failed to create file with error: {err}");
-            return;
+        let bounds = self.state.props.bounds.clone();
+        let mut klines = self.candles.get_ordered_data().vals;
+        if let Some(interval) = resample_interval {
+            klines = resample(&klines, interval);
+        }
+        // indicators warm up on klines loaded before the exported range
+        let keep: Vec<usize> = (0..klines.len())
+            .filter(|i| bounds.contains(klines[*i].t_open))
+            .collect();
+        let rows: Vec<KlineRow> = keep
+            .iter()
+            .map(|i| self.precision.kline_row(&klines[*i]))
+            .collect();
+        let columns: Vec<(String, Vec<String>)> =
+            indicators::columns(&self.indicators, &klines, &self.precision)
+                .into_iter()
+                .map(|(name, vals)| (name, keep.iter().map(|i| vals[*i].clone()).collect()))
+                .collect();
+        if !self.funding.is_empty() {
+            let funding = self
+                .funding
+                .iter()
+                .filter(|f| bounds.contains(f.funding_time));
+            match write_csv(&format!("{name}_funding.csv"), funding) {
+                Ok(abs_path) => info!("exported funding to file: {abs_path:?}"),
+                Err(err) => {
+                    error!("failed to export funding with error: {err}");
+                    self.toasts.error("Failed to export funding");
+                }
+            }
+        }
+        if !self.open_interest.is_empty() {
+            let open_interest = self
+                .open_interest
+                .iter()
+                .filter(|oi| bounds.contains(oi.time));
+            match write_csv(&format!("{name}_open_interest.csv"), open_interest) {
+                Ok(abs_path) => info!("exported open interest to file: {abs_path:?}"),
+                Err(err) => {
+                    error!("failed to export open interest with error: {err}");
+                    self.toasts.error("Failed to export open interest");
+                }
+            }
         }
 
-        let abs_path = path.canonicalize().unwrap();
-        debug!("saving to file: {}", abs_path.display());
+        match write_csv_with_columns(&format!("{name}.csv"), rows.iter(), &columns) {
+            Ok(abs_path) => {
+                self.toasts
+                    .success("File exported")
+                    .set_duration(Some(Duration::from_secs(3)));
+                info!("exported to file: {abs_path:?}");
+            }
+            Err(err) => {
+                error!("failed to export data with error: {err}");
+                self.toasts.error("Failed to export data");
+            }
+        }
+
+        self.export_state.triggered = false;
+    }
+
+    /// Imports files in the pool merging them into the cache one by one.
+    fn start_import(&mut self, paths: Vec<String>) {
+        info!("importing {} files", paths.len());
+
+        let cache = self.cache.clone();
+        let sender = Mutex::new(self.imported_pub.clone());
+        self.pool.execute(move || {
+            paths.iter().for_each(|path| {
+                // only public data dumps are merged into cache, exports are opened as is
+                let res = import_file(&PathBuf::from(path)).and_then(|imported| {
+                    if !imported.exported {
+                        merge_into_cache(&cache, &imported)?;
+                    }
+                    Ok(imported)
+                });
+
+                if let Err(err) = &res {
+                    error!("failed to import file {path}: {err}");
+                }
+
+                if let Err(err) = sender.lock().unwrap().send(res) {
+                    error!("failed to send imported klines to channel: {err}");
+                }
+            });
+        });
+    }
+
+    fn handle_imported(&mut self) {
+        let results: Vec<Result<ImportedKlines, ImportError>> =
+            self.imported_sub.try_iter().collect();
+
+        results.into_iter().for_each(|res| match res {
+            Ok(mut imported) => {
+                self.toasts.success(format!(
+                    "Imported {} klines for {} {}",
+                    imported.klines.len(),
+                    imported.symbol,
+                    imported.interval.name(),
+                ));
+
+                if imported.exported {
+                    self.open_exported(imported);
+                    return;
+                }
 
-        let mut wtr = csv::Writer::from_writer(f_res.unwrap());
-        
-        let data = self.candles.get_ordered_data().vals;
-        data.iter().for_each(|el| {
-            wtr.serialize(el).unwrap();
+                if self.market == Market::Spot
+                    && imported.symbol == self.symbol
+                    && imported.interval == self.state.props.interval
+                {
+                    self.state.add_loaded(imported.bounds);
+                    self.update_data(&mut imported.klines);
+                }
+            }
+            Err(err) => {
+                self.toasts.error(format!("Failed to import file: {err}"));
+            }
         });
+    }
+
+    fn set_precision(&mut self, precision: Precision) {
+        debug!("setting precision: {precision:?}");
 
-        if let Some(err) = wtr.flush().err() {
-            error!("failed to write to file with error: {err}");
-        } else {
-            self.toasts
-                .success("File exported")
-                .set_duration(Some(Duration::from_secs(3)));
-            info!("exported to file: {abs_path:?}");
+        self.precision = precision;
+        self.candles.set_precision(precision);
+    }
+
+    /// Shows exported dataset as is, without downloading missing klines.
+    fn open_exported(&mut self, mut imported: ImportedKlines) {
+        info!(
+            "opening exported klines: symbol: {}, interval: {:?}, bounds: {:?}",
+            imported.symbol, imported.interval, imported.bounds
+        );
+
+        if self.market != imported.market {
+            self.set_market(imported.market);
+            if self.market != imported.market {
+                self.toasts
+                    .error(format!("Can not open {} klines", imported.market.name()));
+                return;
+            }
+        }
+        self.price_kind = imported.kind;
+        self.stop_stream();
+        self.cancel_download();
+        self.live_state.enabled = false;
+
+        self.symbol = imported.symbol.clone();
+        self.load_annotations();
+        self.reset_brick();
+        self.set_precision(Precision::default());
+        self.depth
+            .set_symbol(imported.symbol.clone(), self.precision);
+        if let Err(err) = self.symbol_pub.send(imported.symbol) {
+            error!("failed to send symbol: {err}");
         }
 
-        self.export_state.triggered = false;
+        let props = TimeRangeSettings::new(
+            DateTime::from_utc(
+                NaiveDateTime::from_timestamp(imported.bounds.0 / 1000, 0),
+                Utc,
+            ),
+            DateTime::from_utc(
+                NaiveDateTime::from_timestamp(imported.bounds.1 / 1000, 0),
+                Utc,
+            ),
+            imported.interval,
+        );
+        if let Err(err) = self.props_pub.send(props.clone()) {
+            error!("failed to send props: {err}");
+        }
+
+        self.candles.clear();
+        self.read_only = true;
+        self.integrity = IntegrityReport::default();
+        self.state = State::new(BoundsSet::new(vec![imported.bounds]));
+        self.state.apply_props(&props);
+        self.update_data(&mut imported.klines);
+        self.check_integrity(imported.interval);
     }
 
     fn update(&mut self) {
+        let import_paths = self.import.paths();
+        if !import_paths.is_empty() {
+            self.start_import(import_paths);
+        }
+
+        self.save_annotations();
+
         let drag_wrapped = self.drag_sub.recv_timeout(Duration::from_millis(1));
         if let Ok(bounds) = drag_wrapped {
             debug!("got bounds: {bounds:?}");
@@ -257,27 +982,48 @@ impl Props {
 
             self.export_state.triggered = true;
 
-            self.candles.clear();
             self.start_download(props, true);
         }
 
         let symbol_wrapped = self.symbol_sub.recv_timeout(Duration::from_millis(1));
         if let Ok(symbol) = symbol_wrapped {
-            debug!("got symbol: {symbol}");
+            debug!("got symbol: {}", symbol.symbol);
 
-            self.symbol = symbol.clone();
-            self.symbol_pub.send(symbol).unwrap();
+            if symbol.market() != self.market {
+                self.set_market(symbol.market());
+            }
 
-            self.candles.clear();
+            self.symbol = symbol.symbol.clone();
+            self.load_annotations();
+            self.reset_brick();
+            self.set_precision(Precision::from(&symbol));
+            self.depth.set_symbol(symbol.symbol.clone(), self.precision);
+            self.symbol_pub.send(symbol.symbol).unwrap();
+
+            let mut props = TimeRangeSettings::for_interval(self.state.props.interval);
+            props.display_interval = self.state.props.display_interval;
+            if let Err(err) = self.props_pub.send(props.clone()) {
+                error!("failed to send props: {err}");
+            }
 
-            self.start_download(TimeRangeSettings::default(), true);
+            self.start_download(props, true);
         }
 
         let show_wrapped = self.props_sub.recv_timeout(Duration::from_millis(1));
         if let Ok(props) = show_wrapped {
             debug!("got show button pressed: {props:?}");
 
-            self.start_download(props, true);
+            if props.display_interval != self.state.props.display_interval
+                && props.interval == self.state.props.interval
+                && props.bounds == self.state.props.bounds
+            {
+                debug!("only display interval changed, resampling loaded data");
+                self.candles.set_display_interval(props.resample_interval());
+                self.state.props.display_interval = props.display_interval;
+                self.data_changed = true;
+            } else {
+                self.start_download(props, true);
+            }
         }
 
         let mut got = 0;
@@ -293,12 +1039,18 @@ impl Props {
                 break;
             }
 
-            let klines_res = package_res.unwrap();
-            match klines_res {
+            let tagged = package_res.unwrap();
+            if Some(tagged.request_id) != self.download.as_ref().map(|d| d.id()) {
+                debug!("dropping stale page of download: {}", tagged.request_id);
+                continue;
+            }
+
+            match tagged.result {
                 Ok(klines) => klines.iter().for_each(|k| {
                     res.push(*k);
                 }),
-                Err(_) => {
+                Err(err) => {
+                    error!("got klines result with error: {err}");
                     has_error = true;
                 }
             }
@@ -307,15 +1059,34 @@ impl Props {
         }
 
         if has_error {
+            self.download_failed = true;
             self.toasts.error("Failed to get candles from Binance");
         }
 
         if got > 0 {
             trace!("received {} pages of data", got);
             self.state.loading.inc_loaded_pages(got);
-            self.update_data(&mut res);
+            let replaced = self.update_data(&mut res);
+            // reloaded klines are expected to replace loaded ones
+            if !self.state.reloading() {
+                self.integrity.duplicates += replaced;
+            }
+
+            if self.state.loading.progress() == 1.0 {
+                self.check_integrity(self.state.props.interval);
+                self.save_cache();
+
+                if self.live_state.backfill_pending {
+                    self.backfill_stream();
+                }
+            }
         }
 
+        self.handle_stream_events();
+        self.handle_funding();
+        self.handle_open_interest();
+        self.handle_imported();
+
         if self.state.loading.progress() == 1.0 && self.export_state.triggered {
             self.export_data();
         }
@@ -348,18 +1119,100 @@ impl AppWidget for Props {
 
         TopBottomPanel::top("graph_toolbar").show_inside(ui, |ui| {
             ui.horizontal(|ui| {
+                let mut live = self.live_state.enabled;
+                if ui.checkbox(&mut live, "live").changed() {
+                    info!("setting live mode to: {live}");
+                    self.live_state.enabled = live;
+                    match live {
+                        true => self.start_stream(),
+                        false => self.stop_stream(),
+                    }
+                }
+
+                ui.checkbox(&mut self.show_depth, "depth");
+
+                if self.chart_type.settings_ui(ui, &self.candles.data().vals) {
+                    self.candles.set_chart_type(self.chart_type);
+                    self.data_changed = true;
+                }
+
+                if ui.checkbox(&mut self.show_volume, "volume").changed() {
+                    self.candles.set_show_volume(self.show_volume);
+                    self.data_changed = true;
+                }
+                if self.show_volume
+                    && ui
+                        .checkbox(&mut self.show_taker_buy, "taker buy")
+                        .on_hover_text("Taker buy volume over total volume")
+                        .changed()
+                {
+                    self.candles.set_show_taker_buy(self.show_taker_buy);
+                    self.data_changed = true;
+                }
+
+                match self.candles.annotations().lock() {
+                    Ok(mut annotations) => annotations.toolbar_ui(ui),
+                    Err(err) => error!("failed to lock annotations: {err}"),
+                }
+
+                if self.market != Market::Spot {
+                    let mut kind = self.price_kind;
+                    ComboBox::from_id_source("graph_price_kind")
+                        .selected_text(kind.name())
+                        .show_ui(ui, |ui| {
+                            PriceKind::ALL.iter().for_each(|k| {
+                                ui.selectable_value(&mut kind, *k, k.name());
+                            });
+                        });
+                    if kind != self.price_kind {
+                        info!("setting price kind: {kind:?}");
+                        self.price_kind = kind;
+                        self.start_download(self.state.props.clone(), true);
+                    }
+                }
+
                 if self.state.loading.progress() < 1.0 {
                     ui.add(
                         ProgressBar::new(self.state.loading.progress())
                             .show_percentage()
                             .animate(true),
                     );
+                } else if !self.integrity.is_ok() {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", self.integrity.summary()))
+                            .color(Color32::YELLOW),
+                    )
+                    .on_hover_text(self.integrity.details());
+
+                    if !self.integrity.gaps.is_empty() && ui.button("reload gaps").clicked() {
+                        self.reload_gaps();
+                    }
                 }
             });
         });
 
+        if self.show_depth {
+            TopBottomPanel::bottom("depth_panel")
+                .resizable(true)
+                .min_height(200.0)
+                .show_inside(ui, |ui| {
+                    self.depth.show(ui);
+                });
+        }
+
         CentralPanel::default().show_inside(ui, |ui| {
             self.time_range.show(ui);
+
+            ui.collapsing("Indicators", |ui| {
+                if indicators::settings_ui(ui, &mut self.indicators) {
+                    self.candles.set_indicators(self.indicators.clone());
+                    self.data_changed = true;
+                }
+            });
+
+            ui.collapsing("Import", |ui| {
+                self.import.show(ui);
+            });
         });
 
         self.toasts.show(ui.ctx());
@@ -0,0 +1,89 @@
+use crate::sources::binance::{Interval, Kline};
+
+use super::step::Step;
+
+const MILLIS_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Checks that klines of from interval can be combined into klines of to interval.
+///
+/// Target interval must be coarser and its klines must start on the kline
+/// boundaries of the source interval, e.g. 3m can't be resampled to 5m
+/// and 3d can't be resampled to 1w.
+pub fn can_resample(from: Interval, to: Interval) -> bool {
+    match (from.millis(), to.millis()) {
+        (Some(from), Some(to)) => to > from && to % from == 0,
+        (Some(from), None) => MILLIS_DAY % from == 0,
+        (None, _) => false,
+    }
+}
+
+/// Aggregates klines sorted by open time into klines of the coarser interval.
+///
+/// Klines at the edges of the range may be built from partial data.
+pub fn resample(klines: &[Kline], interval: Interval) -> Vec<Kline> {
+    let step = Step::from(interval);
+
+    klines.iter().fold(Vec::new(), |mut acc, k| {
+        let t_open = interval.open_time(k.t_open);
+
+        match acc.last_mut() {
+            Some(last) if last.t_open == t_open => {
+                last.high = last.high.max(k.high);
+                last.low = last.low.min(k.low);
+                last.close = k.close;
+                last.volume += k.volume;
+                last.quote_asset_volume += k.quote_asset_volume;
+                last.number_of_trades += k.number_of_trades;
+                last.taker_buy_base_asset_volume += k.taker_buy_base_asset_volume;
+                last.taker_buy_quote_asset_volume += k.taker_buy_quote_asset_volume;
+            }
+            _ => acc.push(Kline {
+                t_open,
+                t_close: step.advance(t_open, 1) - 1,
+                ..*k
+            }),
+        }
+
+        acc
+    })
+}
+
+#[cfg(test)]
+mod resample_tests {
+    use crate::widgets::candles::test_klines::minute;
+
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        let klines = vec![
+            minute(3, 1.0, 2.0, 0.5, 1.5),
+            minute(4, 1.5, 3.0, 1.0, 2.5),
+            minute(5, 2.5, 2.6, 2.0, 2.1),
+        ];
+
+        let res = resample(&klines, Interval::Minutes5);
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].t_open, 0);
+        assert_eq!(res[0].t_close, 299_999);
+        assert_eq!(
+            (res[0].open, res[0].high, res[0].low, res[0].close),
+            (1.0, 3.0, 0.5, 2.5)
+        );
+        assert_eq!(res[0].volume, 2.0);
+        assert_eq!(res[0].number_of_trades, 4);
+        assert_eq!(res[1].t_open, 300_000);
+        assert_eq!(res[1].close, 2.1);
+    }
+
+    #[test]
+    fn test_can_resample() {
+        assert!(can_resample(Interval::Minute, Interval::Minutes5));
+        assert!(can_resample(Interval::Hour, Interval::Month));
+        assert!(!can_resample(Interval::Minutes3, Interval::Minutes5));
+        assert!(!can_resample(Interval::Days3, Interval::Week));
+        assert!(!can_resample(Interval::Days3, Interval::Month));
+        assert!(!can_resample(Interval::Hour, Interval::Hour));
+    }
+}
@@ -3,7 +3,10 @@ use tracing::{debug, error};
 use crate::sources::binance::Interval;
 
 use super::{
-    bounds::BoundsSet, loading_state::LoadingState, time_range_settings::TimeRangeSettings,
+    bounds::{Bounds, BoundsSet},
+    loading_state::LoadingState,
+    step::Step,
+    time_range_settings::TimeRangeSettings,
 };
 
 #[derive(Default, Debug, Clone)]
@@ -11,15 +14,47 @@ pub struct State {
     pub loading: LoadingState,
     pub props: TimeRangeSettings,
     bounds: BoundsSet,
+    reloading: bool,
 }
 
 impl State {
+    /// Creates state treating bounds as already loaded, e.g. from cache.
+    pub fn new(loaded: BoundsSet) -> Self {
+        Self {
+            bounds: loaded,
+            ..Default::default()
+        }
+    }
+
+    /// Bounds which are loaded or being loaded.
+    pub fn bounds(&self) -> &BoundsSet {
+        &self.bounds
+    }
+
+    /// Checks if loading bounds are already covered, so received klines replace loaded ones.
+    pub fn reloading(&self) -> bool {
+        self.reloading
+    }
+
+    /// Registers bounds loaded from another source, e.g. imported from file.
+    pub fn add_loaded(&mut self, bounds: Bounds) {
+        self.bounds = self.bounds.merge_single(bounds);
+    }
+
     pub fn apply_props(&mut self, props: &TimeRangeSettings) {
-        debug!("applying new props: {props:?}");
+        self.apply_props_with_step(props, State::step(props.interval), props.limit);
+    }
+
+    /// Applies props splitting missing bounds to pages with custom step and limit.
+    pub fn apply_props_with_step(&mut self, props: &TimeRangeSettings, step: Step, limit: usize) {
+        debug!("applying new props: {props:?}; step: {step:?}; limit: {limit}");
 
         self.props = props.clone();
+        self.reloading = false;
 
-        let subtract_res = props.bounds.subtract(&self.bounds);
+        // bounds in the future are not marked as loaded so they are requested once they pass
+        let bounds = props.loadable_bounds();
+        let subtract_res = bounds.subtract(&self.bounds);
         if subtract_res.is_none() {
             debug!("found nothing to load");
             self.loading = LoadingState::default();
@@ -28,7 +63,7 @@ impl State {
         let to_load = subtract_res.unwrap();
         debug!("computed difference to load: {to_load:?}");
 
-        let loading_res = LoadingState::new(&to_load, State::step(props.interval), props.limit);
+        let loading_res = LoadingState::new(&to_load, step, limit);
         if loading_res.is_none() {
             error!("failed to initialize loading state");
             return;
@@ -36,7 +71,7 @@ impl State {
         let loading = loading_res.unwrap();
         debug!("initialized loading state: {loading:?}");
 
-        let new_bounds = self.bounds.merge(&props.bounds);
+        let new_bounds = self.bounds.merge(&bounds);
         debug!("computed new_bounds: {new_bounds:?}");
 
         self.loading = loading;
@@ -44,11 +79,48 @@ impl State {
         self.props = props.clone();
     }
 
-    fn step(i: Interval) -> usize {
-        match i {
-            Interval::Minute => 60 * 1000,
-            Interval::Hour => 60 * 60 * 1000,
-            Interval::Day => 60 * 60 * 24 * 1000,
+    /// Loads bounds again even if they are already covered, e.g. to fill gaps in loaded data.
+    pub fn reload(&mut self, bounds: &BoundsSet) {
+        debug!("reloading bounds: {bounds:?}");
+
+        match LoadingState::new(bounds, State::step(self.props.interval), self.props.limit) {
+            Some(loading) => {
+                self.loading = loading;
+                self.reloading = true;
+            }
+            None => error!("failed to initialize loading state"),
         }
     }
+
+    fn step(i: Interval) -> Step {
+        Step::from(i)
+    }
+}
+
+#[cfg(test)]
+mod state_tests {
+    use chrono::Duration;
+
+    use crate::sources::clock;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_props_future() {
+        let now = clock::now();
+        let props = TimeRangeSettings::new(
+            now - Duration::hours(2),
+            now + Duration::hours(2),
+            Interval::Hour,
+        );
+
+        let mut state = State::default();
+        state.apply_props(&props);
+
+        let loaded = state.bounds().vals();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, props.bounds.vals()[0].0);
+        assert!(loaded[0].1 <= clock::now_millis());
+        assert!(state.loading.pages() > 0);
+    }
 }
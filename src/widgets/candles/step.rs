@@ -0,0 +1,103 @@
+use crate::sources::binance::{add_months, Interval};
+
+/// Distance between open times of 2 consequent klines.
+///
+/// Months have variable length so they are counted in calendar units
+/// instead of millis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Millis(i64),
+    Months(i64),
+}
+
+impl Default for Step {
+    fn default() -> Self {
+        Step::Millis(0)
+    }
+}
+
+impl From<Interval> for Step {
+    fn from(i: Interval) -> Self {
+        match i.millis() {
+            Some(millis) => Step::Millis(millis),
+            None => Step::Months(1),
+        }
+    }
+}
+
+impl Step {
+    pub fn valid(&self) -> bool {
+        match self {
+            Step::Millis(m) => *m > 0,
+            Step::Months(m) => *m > 0,
+        }
+    }
+
+    /// Moves ts by n steps. Negative n moves ts to the past.
+    pub fn advance(&self, ts: i64, n: i64) -> i64 {
+        match self {
+            Step::Millis(m) => ts + m * n,
+            Step::Months(m) => add_months(ts, m * n),
+        }
+    }
+
+    /// Counts steps needed to cover range from start (included) to end (not included).
+    ///
+    /// Partial step at the end is counted too as a kline opens in it, so page of
+    /// unaligned bounds requests its last kline instead of dropping it.
+    pub fn count(&self, start: i64, end: i64) -> usize {
+        if end <= start || !self.valid() {
+            return 0;
+        }
+
+        match self {
+            Step::Millis(m) => ((end - start + m - 1) / m) as usize,
+            Step::Months(_) => {
+                let mut n = 0;
+                while self.advance(start, n) < end {
+                    n += 1;
+                }
+
+                n as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod step_tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        assert_eq!(Step::Millis(1).count(0, 50), 50);
+        assert_eq!(Step::Millis(2).count(0, 50), 25);
+        assert_eq!(Step::Millis(2).count(0, 51), 26);
+        assert_eq!(Step::Millis(2).count(10, 10), 0);
+
+        let jan = NaiveDate::from_ymd(2022, 1, 1)
+            .and_hms(0, 0, 0)
+            .timestamp_millis();
+        let apr = NaiveDate::from_ymd(2022, 4, 1)
+            .and_hms(0, 0, 0)
+            .timestamp_millis();
+        assert_eq!(Step::Months(1).count(jan, apr), 3);
+        assert_eq!(Step::Months(1).count(jan, apr + 1), 4);
+    }
+
+    #[test]
+    fn test_advance() {
+        assert_eq!(Step::Millis(60).advance(0, 2), 120);
+        assert_eq!(Step::Millis(60).advance(120, -2), 0);
+
+        let jan = NaiveDate::from_ymd(2022, 1, 1)
+            .and_hms(0, 0, 0)
+            .timestamp_millis();
+        let mar = NaiveDate::from_ymd(2022, 3, 1)
+            .and_hms(0, 0, 0)
+            .timestamp_millis();
+        assert_eq!(Step::Months(1).advance(jan, 2), mar);
+    }
+}
@@ -1,58 +1,359 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, Utc};
 use crossbeam::channel::{unbounded, Sender};
-use egui::{Layout, ScrollArea, TextEdit, WidgetText};
+use egui::{
+    CollapsingHeader, Color32, ComboBox, DragValue, Grid, Layout, RichText, ScrollArea, TextEdit,
+    WidgetText,
+};
 use poll_promise::Promise;
 use tracing::{debug, error, info};
 
 use crate::{
     netstrat::line_filter_highlight_layout,
-    sources::binance::{Client, Info, Symbol},
+    sources::{
+        binance::{errors::ClientError, Client, Market, Symbol, Ticker24h},
+        MarketDataSource,
+    },
     widgets::AppWidget,
 };
 
-#[derive(Default)]
+use super::precision::Precision;
+use super::symbols_cache::{SymbolsCache, SymbolsEntry};
+
+#[derive(Default, Clone, PartialEq)]
 struct FilterProps {
     value: String,
     active_only: bool,
+    /// Quote asset symbols should have. Empty value matches any asset.
+    quote_asset: String,
+    /// Minimal 24h quote volume. Symbols without stats are hidden if it is set.
+    min_volume: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Symbol,
+    LastPrice,
+    Change,
+    QuoteVolume,
+    Trades,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 5] = [
+        SortColumn::Symbol,
+        SortColumn::LastPrice,
+        SortColumn::Change,
+        SortColumn::QuoteVolume,
+        SortColumn::Trades,
+    ];
+
+    fn name(&self) -> &str {
+        match self {
+            SortColumn::Symbol => "symbol",
+            SortColumn::LastPrice => "last",
+            SortColumn::Change => "24h %",
+            SortColumn::QuoteVolume => "volume",
+            SortColumn::Trades => "trades",
+        }
+    }
+
+    /// Value of the column for the ticker. Symbol column is compared by name.
+    fn value(&self, ticker: &Ticker24h) -> f64 {
+        match self {
+            SortColumn::Symbol => 0.0,
+            SortColumn::LastPrice => ticker.last_price,
+            SortColumn::Change => ticker.price_change_percent,
+            SortColumn::QuoteVolume => ticker.quote_volume,
+            SortColumn::Trades => ticker.count as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SortProps {
+    column: SortColumn,
+    descending: bool,
+}
+
+impl Default for SortProps {
+    fn default() -> Self {
+        Self {
+            column: SortColumn::Symbol,
+            descending: false,
+        }
+    }
 }
 
 pub struct Symbols {
     symbols: Vec<Symbol>,
     filter: FilterProps,
+    sort: SortProps,
     filtered: Vec<Symbol>,
+    /// Quote assets of loaded symbols used in the quote asset filter.
+    quote_assets: Vec<String>,
+    /// 24h stats by symbol name.
+    tickers: HashMap<String, Ticker24h>,
     loading: bool,
+    /// Error of the last symbols request.
+    error: Option<String>,
+    /// Error of the last 24h stats request.
+    tickers_error: Option<String>,
+    /// Time in millis the shown symbols list was fetched at.
+    fetched_at: Option<i64>,
     selected_symbol: String,
-    symbols_promise: Option<Promise<Info>>,
-    symbol_pub: Sender<String>,
+    symbols_promise: Option<Promise<Result<SymbolsEntry, ClientError>>>,
+    tickers_promise: Option<Promise<Result<Vec<Ticker24h>, ClientError>>>,
+    symbol_pub: Sender<Symbol>,
+    /// Market symbols are shown for.
+    market: Market,
+    source: Arc<dyn MarketDataSource>,
+    /// Sources of markets user can switch between.
+    sources: Vec<(Market, Arc<dyn MarketDataSource>)>,
+    cache: SymbolsCache,
 }
 
 impl Default for Symbols {
     fn default() -> Self {
         let (s, _) = unbounded();
-        let symbols_promise = Some(Promise::spawn_blocking(Client::info));
-        let loading = true;
-        Self {
-            loading,
-            symbols_promise,
-            symbol_pub: s,
+        Symbols::new(s, Arc::new(Client::default()))
+    }
+}
+
+impl Symbols {
+    pub fn new(symbol_pub: Sender<Symbol>, source: Arc<dyn MarketDataSource>) -> Self {
+        info!("initing widget symbols");
+        let mut symbols = Self {
+            symbol_pub,
+            sources: vec![(Market::Spot, source.clone())],
+            source,
+            market: Default::default(),
+            loading: Default::default(),
+            error: Default::default(),
+            tickers_error: Default::default(),
+            fetched_at: Default::default(),
+            symbols_promise: Default::default(),
+            tickers_promise: Default::default(),
+            cache: Default::default(),
             symbols: Default::default(),
             filter: Default::default(),
+            sort: Default::default(),
             filtered: Default::default(),
+            quote_assets: Default::default(),
+            tickers: Default::default(),
             selected_symbol: Default::default(),
+        };
+
+        symbols.request_symbols();
+        symbols.request_tickers();
+
+        symbols
+    }
+
+    /// Lets user switch to futures symbols loaded from the source.
+    pub fn with_futures(mut self, source: Arc<dyn MarketDataSource>) -> Self {
+        self.sources.push((Market::UsdFutures, source));
+        self
+    }
+
+    fn set_market(&mut self, market: Market) {
+        let source = match self.sources.iter().find(|(m, _)| *m == market) {
+            Some((_, source)) => source.clone(),
+            None => {
+                error!("no source for market: {}", market.name());
+                return;
+            }
+        };
+
+        info!("setting market: {}", market.name());
+
+        self.market = market;
+        self.source = source;
+        self.symbols = Default::default();
+        self.filtered = Default::default();
+        self.quote_assets = Default::default();
+        self.tickers = Default::default();
+        self.fetched_at = None;
+        self.error = None;
+        self.tickers_error = None;
+        self.filter.quote_asset = Default::default();
+
+        self.request_symbols();
+        self.request_tickers();
+    }
+
+    /// Requests symbols list saving it to cache on success.
+    ///
+    /// Cached list is shown meanwhile so the widget is usable offline or on slow network.
+    fn request_symbols(&mut self) {
+        if self.symbols.is_empty() {
+            if let Some(entry) = self.cache.load(self.market) {
+                info!("showing symbols cached at: {}", entry.fetched_at);
+                self.set_entry(entry);
+            }
         }
+
+        info!("requesting symbols");
+
+        let source = self.source.clone();
+        let cache = self.cache.clone();
+        let market = self.market;
+        self.loading = true;
+        self.symbols_promise = Some(Promise::spawn_blocking(move || {
+            let entry = SymbolsEntry {
+                info: source.info()?,
+                fetched_at: Utc::now().timestamp_millis(),
+            };
+
+            if let Err(err) = cache.save(market, &entry) {
+                error!("failed to save symbols to cache: {err}");
+            }
+
+            Ok(entry)
+        }));
     }
-}
 
-impl Symbols {
-    pub fn new(symbol_pub: Sender<String>) -> Self {
-        info!("initing widget symbols");
-        Self {
-            symbol_pub,
-            ..Default::default()
+    fn handle_symbols_result(&mut self) {
+        let result = match self.symbols_promise.take() {
+            Some(promise) => match promise.try_take() {
+                Ok(result) => result,
+                Err(promise) => {
+                    self.symbols_promise = Some(promise);
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        self.loading = false;
+
+        let entry = match result {
+            Ok(entry) => {
+                self.error = None;
+                entry
+            }
+            Err(err) => {
+                error!("failed to get symbols: {err}");
+                self.error = Some(err.to_string());
+                return;
+            }
+        };
+
+        self.set_entry(entry);
+    }
+
+    fn set_entry(&mut self, entry: SymbolsEntry) {
+        self.symbols = entry.info.symbols;
+        self.fetched_at = Some(entry.fetched_at);
+
+        self.quote_assets = self
+            .symbols
+            .iter()
+            .map(|s| s.quote_asset().to_string())
+            .collect();
+        self.quote_assets.sort();
+        self.quote_assets.dedup();
+
+        self.refilter();
+    }
+
+    fn request_tickers(&mut self) {
+        info!("requesting 24h stats");
+
+        let source = self.source.clone();
+        self.tickers_promise = Some(Promise::spawn_blocking(move || source.ticker_24h()));
+    }
+
+    fn handle_tickers_result(&mut self) {
+        let result = match self.tickers_promise.take() {
+            Some(promise) => match promise.try_take() {
+                Ok(result) => result,
+                Err(promise) => {
+                    self.tickers_promise = Some(promise);
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        match result {
+            Ok(tickers) => {
+                info!("got 24h stats for {} symbols", tickers.len());
+                self.tickers_error = None;
+                self.tickers = tickers.into_iter().map(|t| (t.symbol.clone(), t)).collect();
+                self.refilter();
+            }
+            Err(err) => {
+                error!("failed to get 24h stats: {err}");
+                self.tickers_error = Some(err.to_string());
+            }
         }
     }
 
-    fn update(&mut self, filter_value: String, active_only: bool, selected_symbol: String) {
-        self.apply_filter(filter_value, active_only);
+    fn format_fetched_at(ts: i64) -> String {
+        NaiveDateTime::from_timestamp(ts / 1000, 0)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    /// Shows trading rules of the selected symbol.
+    fn show_details(ui: &mut egui::Ui, symbol: &Symbol) {
+        CollapsingHeader::new(WidgetText::from(format!("{} details", symbol.symbol)).small())
+            .id_source("symbol_details")
+            .show(ui, |ui| {
+                Grid::new("symbol_details_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("assets");
+                        ui.label(format!("{}/{}", symbol.base_asset(), symbol.quote_asset()));
+                        ui.end_row();
+
+                        ui.label("precision");
+                        ui.label(format!(
+                            "{}/{}",
+                            symbol.base_asset_precision(),
+                            symbol.quote_asset_precision()
+                        ));
+                        ui.end_row();
+
+                        ui.label("status");
+                        ui.label(&symbol.status);
+                        ui.end_row();
+
+                        ui.label("order types");
+                        ui.label(
+                            WidgetText::from(
+                                symbol
+                                    .order_types()
+                                    .iter()
+                                    .map(|t| t.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                            )
+                            .small(),
+                        );
+                        ui.end_row();
+                    });
+
+                ui.add_space(3f32);
+                symbol.filters().iter().for_each(|f| {
+                    ui.label(WidgetText::from(f.to_string()).small());
+                });
+            });
+    }
+
+    fn update(&mut self, filter: FilterProps, sort: SortProps, selected_symbol: String) {
+        self.apply_filter(filter);
+
+        if self.sort != sort {
+            info!("sorting symbols by: {:?}", sort);
+            self.sort = sort;
+            self.sort_filtered();
+        }
 
         if self.selected_symbol != selected_symbol {
             info!("setting symbol to {selected_symbol}");
@@ -60,123 +361,374 @@ impl Symbols {
         }
     }
 
-    fn apply_filter(&mut self, filter_value: String, active_only: bool) {
-        let filter_normalized = filter_value.to_lowercase();
-        if filter_normalized == self.filter.value && active_only == self.filter.active_only {
+    fn apply_filter(&mut self, mut filter: FilterProps) {
+        filter.value = filter.value.to_lowercase();
+        if filter == self.filter {
             return;
         }
 
-        info!("applying filter: {filter_value}; active_only: {active_only}");
+        info!(
+            "applying filter: {}; active_only: {}; quote_asset: {}; min_volume: {}",
+            filter.value, filter.active_only, filter.quote_asset, filter.min_volume
+        );
 
-        if filter_normalized != self.filter.value
-            && filter_normalized.contains(self.filter.value.as_str())
-            && self.filter.active_only == active_only
-        {
+        let only_narrowed = filter.value != self.filter.value
+            && filter.value.contains(self.filter.value.as_str())
+            && FilterProps {
+                value: self.filter.value.clone(),
+                ..filter.clone()
+            } == self.filter;
+
+        self.filter = filter;
+
+        if only_narrowed {
             debug!("using optimized version");
 
-            self.filtered.retain(|el| {
-                el.symbol
-                    .to_lowercase()
-                    .contains(filter_normalized.as_str())
-            });
-        } else {
-            debug!("using heavy version");
+            let value = self.filter.value.as_str();
+            self.filtered
+                .retain(|el| el.symbol.to_lowercase().contains(value));
+            return;
+        }
+
+        self.refilter();
+    }
 
-            self.filtered = self
-                .symbols
-                .iter()
-                .filter(|el| {
-                    el.symbol
-                        .to_lowercase()
-                        .contains(filter_normalized.as_str())
-                })
-                .cloned()
-                .collect();
+    /// Filters all symbols from scratch and sorts them.
+    fn refilter(&mut self) {
+        debug!("using heavy version");
+
+        self.filtered = self
+            .symbols
+            .iter()
+            .filter(|el| self.matches(el))
+            .cloned()
+            .collect();
+
+        self.sort_filtered();
+    }
+
+    fn matches(&self, symbol: &Symbol) -> bool {
+        let f = &self.filter;
+
+        symbol.symbol.to_lowercase().contains(f.value.as_str())
+            && (!f.active_only || symbol.active())
+            && (f.quote_asset.is_empty() || symbol.quote_asset() == f.quote_asset)
+            && (f.min_volume <= 0.0
+                || matches!(self.tickers.get(&symbol.symbol), Some(t) if t.quote_volume >= f.min_volume))
+    }
+
+    fn sort_filtered(&mut self) {
+        sort_symbols(&mut self.filtered, &self.tickers, self.sort);
+    }
+
+    fn sort_header(ui: &mut egui::Ui, sort: &mut SortProps, column: SortColumn) {
+        let mut text = column.name().to_string();
+        if sort.column == column {
+            text += if sort.descending { " ⏷" } else { " ⏶" };
         }
 
-        if active_only != self.filter.active_only && active_only {
-            self.filtered.retain(|el| el.active() == active_only);
+        if ui
+            .selectable_label(sort.column == column, RichText::new(text).small().strong())
+            .clicked()
+        {
+            match sort.column == column {
+                true => sort.descending = !sort.descending,
+                false => {
+                    sort.column = column;
+                    sort.descending = column != SortColumn::Symbol;
+                }
+            }
+        }
+    }
+}
+
+/// Sorts symbols by the column. Symbols without stats go after symbols with stats.
+fn sort_symbols(symbols: &mut [Symbol], tickers: &HashMap<String, Ticker24h>, sort: SortProps) {
+    symbols.sort_by(|a, b| {
+        let ord = match sort.column {
+            SortColumn::Symbol => a.symbol.cmp(&b.symbol),
+            column => match (tickers.get(&a.symbol), tickers.get(&b.symbol)) {
+                (Some(a), Some(b)) => column.value(a).total_cmp(&column.value(b)),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+
+        match sort.descending {
+            true => ord.reverse(),
+            false => ord,
         }
+    });
+}
 
-        self.filter.value = filter_normalized;
-        self.filter.active_only = active_only;
+/// Formats big values with metric suffixes to fit narrow columns.
+fn format_compact(v: f64) -> String {
+    match v.abs() {
+        a if a >= 1e9 => format!("{:.2}B", v / 1e9),
+        a if a >= 1e6 => format!("{:.2}M", v / 1e6),
+        a if a >= 1e3 => format!("{:.2}K", v / 1e3),
+        _ => format!("{:.2}", v),
     }
 }
 
 impl AppWidget for Symbols {
     fn show(&mut self, ui: &mut egui::Ui) {
-        let mut filter_value = self.filter.value.clone();
-        let mut active_only = self.filter.active_only;
+        let mut filter = self.filter.clone();
+        let mut sort = self.sort;
         let mut selected_symbol = self.selected_symbol.clone();
 
-        if let Some(promise) = &self.symbols_promise {
-            if let Some(result) = promise.ready() {
-                self.loading = false;
+        self.handle_symbols_result();
+        self.handle_tickers_result();
 
-                self.symbols = result.symbols.to_vec();
-                self.filtered = result.symbols.to_vec();
-
-                self.symbols_promise = None;
+        if self.sources.len() > 1 {
+            let mut market = self.market;
+            ComboBox::from_id_source("symbols_market")
+                .selected_text(market.name())
+                .show_ui(ui, |ui| {
+                    self.sources.iter().for_each(|(m, _)| {
+                        ui.selectable_value(&mut market, *m, m.name());
+                    });
+                });
+            if market != self.market {
+                self.set_market(market);
             }
         }
 
-        if self.loading {
+        if self.loading && self.symbols.is_empty() {
             ui.centered_and_justified(|ui| {
                 ui.spinner();
             });
             return;
         }
 
+        if let Some(err) = self.error.clone() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(WidgetText::from(format!("Failed to load symbols: {err}")).small());
+                if self.loading {
+                    ui.spinner();
+                } else if ui.button("retry").clicked() {
+                    self.request_symbols();
+                }
+            });
+        }
+
+        if self.symbols.is_empty() {
+            return;
+        }
+
         ui.with_layout(Layout::top_down(egui::Align::LEFT), |ui| {
             ui.add(
-                TextEdit::singleline(&mut filter_value)
+                TextEdit::singleline(&mut filter.value)
                     .hint_text(WidgetText::from("filter symbols").italics()),
             );
 
             ui.with_layout(Layout::top_down(egui::Align::RIGHT), |ui| {
-                ui.checkbox(&mut active_only, "active only");
+                ui.checkbox(&mut filter.active_only, "active only");
+
+                ComboBox::from_id_source("symbols_quote_asset")
+                    .selected_text(match filter.quote_asset.is_empty() {
+                        true => "any quote",
+                        false => filter.quote_asset.as_str(),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut filter.quote_asset, String::new(), "any quote");
+                        self.quote_assets.iter().for_each(|a| {
+                            ui.selectable_value(&mut filter.quote_asset, a.clone(), a);
+                        });
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        DragValue::new(&mut filter.min_volume)
+                            .speed(1000.0)
+                            .clamp_range(0.0..=f64::MAX),
+                    );
+                    ui.label(WidgetText::from("min volume").small());
+                });
+
+                ui.horizontal(|ui| {
+                    if self.tickers_promise.is_some() {
+                        ui.spinner();
+                    } else if ui.small_button("refresh stats").clicked() {
+                        self.request_tickers();
+                    }
+                    if let Some(err) = &self.tickers_error {
+                        ui.label(WidgetText::from("⚠").small())
+                            .on_hover_text(format!("Failed to load 24h stats: {err}"));
+                    }
+                });
+
                 ui.label(
                     WidgetText::from(format!("{}/{}", self.filtered.len(), self.symbols.len()))
                         .small(),
                 );
+                if let Some(fetched_at) = self.fetched_at {
+                    ui.horizontal(|ui| {
+                        if self.loading {
+                            ui.spinner();
+                        }
+                        ui.label(
+                            WidgetText::from(format!(
+                                "fetched at {}",
+                                Symbols::format_fetched_at(fetched_at)
+                            ))
+                            .small(),
+                        );
+                    });
+                }
             });
 
+            if let Some(symbol) = self.symbols.iter().find(|s| s.symbol == selected_symbol) {
+                Symbols::show_details(ui, symbol);
+            }
+
             ui.add_space(5f32);
 
-            ScrollArea::vertical()
+            ScrollArea::both()
                 .auto_shrink([false; 2])
                 .max_height(ui.available_height())
                 .show(ui, |ui| {
-                    ui.with_layout(Layout::top_down(egui::Align::LEFT), |ui| {
-                        self.filtered.iter().for_each(|s| {
-                            let label = ui.selectable_label(
-                                s.symbol == selected_symbol,
-                                WidgetText::from(line_filter_highlight_layout(
-                                    ui,
-                                    &s.symbol,
-                                    &self.filter.value,
-                                    !s.active(),
-                                )),
-                            );
-
-                            if label.clicked() {
-                                let send_result = self.symbol_pub.send(s.symbol.clone());
-                                match send_result {
-                                    Ok(_) => {
-                                        debug!("sent symbol: {}", s.symbol);
+                    Grid::new("symbols_table")
+                        .num_columns(SortColumn::ALL.len())
+                        .striped(true)
+                        .show(ui, |ui| {
+                            SortColumn::ALL.iter().for_each(|c| {
+                                Symbols::sort_header(ui, &mut sort, *c);
+                            });
+                            ui.end_row();
+
+                            self.filtered.iter().for_each(|s| {
+                                let label = ui.selectable_label(
+                                    s.symbol == selected_symbol,
+                                    WidgetText::from(line_filter_highlight_layout(
+                                        ui,
+                                        &s.symbol,
+                                        &self.filter.value,
+                                        !s.active(),
+                                    )),
+                                );
+
+                                if label.clicked() {
+                                    let send_result = self.symbol_pub.send(s.clone());
+                                    match send_result {
+                                        Ok(_) => {
+                                            debug!("sent symbol: {}", s.symbol);
+                                        }
+                                        Err(err) => {
+                                            error!("failed to send symbol: {err}");
+                                        }
                                     }
-                                    Err(err) => {
-                                        error!("failed to send symbol: {err}");
+
+                                    selected_symbol = s.symbol.clone();
+                                };
+
+                                match self.tickers.get(&s.symbol) {
+                                    Some(t) => {
+                                        let change_color = match t.price_change_percent < 0.0 {
+                                            true => Color32::LIGHT_RED,
+                                            false => Color32::LIGHT_GREEN,
+                                        };
+
+                                        ui.label(
+                                            RichText::new(Precision::from(s).price(t.last_price))
+                                                .small(),
+                                        );
+                                        ui.label(
+                                            RichText::new(format!(
+                                                "{:.2}%",
+                                                t.price_change_percent
+                                            ))
+                                            .small()
+                                            .color(change_color),
+                                        );
+                                        ui.label(
+                                            RichText::new(format_compact(t.quote_volume)).small(),
+                                        );
+                                        ui.label(RichText::new(t.count.to_string()).small());
                                     }
+                                    None => (1..SortColumn::ALL.len()).for_each(|_| {
+                                        ui.label(RichText::new("-").small());
+                                    }),
                                 }
-
-                                selected_symbol = s.symbol.clone();
-                            };
+                                ui.end_row();
+                            });
                         });
-                    })
                 });
         });
 
-        self.update(filter_value, active_only, selected_symbol);
+        self.update(filter, sort, selected_symbol);
+    }
+}
+
+#[cfg(test)]
+mod symbols_tests {
+    use super::*;
+
+    fn symbol(name: &str) -> Symbol {
+        serde_json::from_value(serde_json::json!({
+            "symbol": name,
+            "status": "TRADING",
+            "baseAsset": "A",
+            "baseAssetPrecision": 8,
+            "quoteAsset": "B",
+            "quotePrecision": 8,
+            "quoteAssetPrecision": 8,
+            "baseCommissionPrecision": 8,
+            "quoteCommissionPrecision": 8,
+            "icebergAllowed": true,
+            "ocoAllowed": true,
+            "quoteOrderQtyMarketAllowed": true,
+            "allowTrailingStop": true,
+            "isSpotTradingAllowed": true,
+            "isMarginTradingAllowed": true
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sort_symbols() {
+        let mut symbols = vec![symbol("A"), symbol("B"), symbol("C")];
+        let tickers: HashMap<String, Ticker24h> = [("A", 10.0), ("C", 20.0)]
+            .into_iter()
+            .map(|(s, v)| {
+                (
+                    s.to_string(),
+                    Ticker24h {
+                        symbol: s.to_string(),
+                        quote_volume: v,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        sort_symbols(
+            &mut symbols,
+            &tickers,
+            SortProps {
+                column: SortColumn::QuoteVolume,
+                descending: true,
+            },
+        );
+        let names: Vec<&str> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, vec!["C", "A", "B"]);
+
+        sort_symbols(
+            &mut symbols,
+            &tickers,
+            SortProps {
+                column: SortColumn::Symbol,
+                descending: false,
+            },
+        );
+        let names: Vec<&str> = symbols.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, vec!["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_format_compact() {
+        assert_eq!(format_compact(1_234_567.0), "1.23M");
+        assert_eq!(format_compact(999.0), "999.00");
     }
 }
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sources::binance::{Info, Market};
+
+use super::{error::CacheError, json_store::JsonStore};
+
+const CACHE_DIR: &str = ".cache";
+
+/// Exchange info together with the time it was fetched at.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SymbolsEntry {
+    pub fetched_at: i64,
+    pub info: Info,
+}
+
+/// On disk storage of the last successfully fetched symbols list of every market.
+#[derive(Debug, Clone)]
+pub struct SymbolsCache {
+    store: JsonStore,
+}
+
+impl Default for SymbolsCache {
+    fn default() -> Self {
+        Self::new(PathBuf::from(CACHE_DIR))
+    }
+}
+
+impl SymbolsCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            store: JsonStore::new(dir),
+        }
+    }
+
+    /// Returns cached entry or None if there is nothing cached or cache is unreadable.
+    pub fn load(&self, market: Market) -> Option<SymbolsEntry> {
+        self.store.load(&SymbolsCache::name(market))
+    }
+
+    pub fn save(&self, market: Market, entry: &SymbolsEntry) -> Result<(), CacheError> {
+        self.store.save(&SymbolsCache::name(market), entry)
+    }
+
+    fn name(market: Market) -> String {
+        match market {
+            Market::Spot => "symbols".to_string(),
+            _ => format!("symbols_{}", market.code()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod symbols_cache_tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_save_load() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let cache = SymbolsCache::new(dir.clone());
+
+        assert!(cache.load(Market::Spot).is_none());
+
+        let info = serde_json::from_str::<Info>(
+            r#"{"symbols":[{"symbol":"ETHBTC","status":"TRADING","baseAsset":"ETH","baseAssetPrecision":8,"quoteAsset":"BTC","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"allowTrailingStop":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true}]}"#,
+        )
+        .unwrap();
+        cache
+            .save(
+                Market::Spot,
+                &SymbolsEntry {
+                    fetched_at: 42,
+                    info,
+                },
+            )
+            .unwrap();
+
+        let entry = cache.load(Market::Spot).unwrap();
+        assert_eq!(entry.fetched_at, 42);
+        assert_eq!(entry.info.symbols[0].symbol, "ETHBTC");
+        assert!(cache.load(Market::UsdFutures).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
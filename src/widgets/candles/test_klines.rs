@@ -0,0 +1,16 @@
+use crate::sources::binance::Kline;
+
+/// Kline of the n-th minute since epoch with the given prices.
+pub fn minute(n: i64, open: f64, high: f64, low: f64, close: f64) -> Kline {
+    Kline {
+        t_open: n * 60_000,
+        t_close: (n + 1) * 60_000 - 1,
+        open,
+        high,
+        low,
+        close,
+        volume: 1.0,
+        number_of_trades: 2,
+        ..Default::default()
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
 
 use super::{
     bounds::{Bounds, BoundsSet},
+    resample::can_resample,
     time_range_settings::TimeRangeSettings,
 };
 
@@ -17,6 +18,8 @@ pub struct TimeRange {
     time_start_input: TimeInput,
     time_end_input: TimeInput,
     interval: Interval,
+    display_interval: Interval,
+    show_interval: bool,
 
     symbol: String,
     valid: bool,
@@ -50,6 +53,8 @@ impl TimeRange {
             date_start: props.date_start,
             date_end: props.date_end,
             interval: props.interval,
+            display_interval: props.display_interval,
+            show_interval: true,
             time_start_input: TimeInput::new(
                 props.time_start.hour(),
                 props.time_start.minute(),
@@ -65,12 +70,19 @@ impl TimeRange {
 }
 
 impl TimeRange {
+    /// Hides interval picker for widgets which do not depend on interval.
+    pub fn without_interval(mut self) -> Self {
+        self.show_interval = false;
+        self
+    }
+
     fn parse_props(
         time_start_opt: Option<NaiveTime>,
         time_end_opt: Option<NaiveTime>,
         date_start: Date<Utc>,
         date_end: Date<Utc>,
         interval: Interval,
+        display_interval: Interval,
     ) -> Option<TimeRangeSettings> {
         if time_start_opt.is_none() || time_end_opt.is_none() {
             return None;
@@ -85,6 +97,7 @@ impl TimeRange {
             time_start,
             time_end,
             interval,
+            display_interval,
             bounds: BoundsSet::new(vec![]),
             limit: 1000,
         };
@@ -102,6 +115,8 @@ impl TimeRange {
 
         self.date_start = p.date_start;
         self.date_end = p.date_end;
+        self.interval = p.interval;
+        self.display_interval = p.display_interval;
 
         let time_start = p.time_start;
         self.time_start_input =
@@ -165,26 +180,57 @@ impl AppWidget for TimeRange {
                         ui.label("Time End");
                     });
                 });
-                ui.collapsing("Interval", |ui| {
-                    egui::ComboBox::from_label("pick data interval")
-                        .selected_text(format!("{:?}", self.interval))
-                        .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut self.interval, Interval::Day, "Day");
-                            ui.selectable_value(&mut self.interval, Interval::Hour, "Hour");
-                            ui.selectable_value(&mut self.interval, Interval::Minute, "Minute");
-                        });
-                });
+                let mut display_changed = false;
+                if self.show_interval {
+                    ui.collapsing("Interval", |ui| {
+                        egui::ComboBox::from_label("pick data interval")
+                            .selected_text(self.interval.name())
+                            .show_ui(ui, |ui| {
+                                Interval::ALL.iter().rev().for_each(|i| {
+                                    ui.selectable_value(&mut self.interval, *i, i.name());
+                                });
+                            });
+
+                        if self.display_interval != self.interval
+                            && !can_resample(self.interval, self.display_interval)
+                        {
+                            self.display_interval = self.interval;
+                        }
+
+                        egui::ComboBox::from_label("pick display interval")
+                            .selected_text(self.display_interval.name())
+                            .show_ui(ui, |ui| {
+                                Interval::ALL
+                                    .iter()
+                                    .rev()
+                                    .filter(|i| {
+                                        **i == self.interval || can_resample(self.interval, **i)
+                                    })
+                                    .for_each(|i| {
+                                        display_changed |= ui
+                                            .selectable_value(
+                                                &mut self.display_interval,
+                                                *i,
+                                                i.name(),
+                                            )
+                                            .changed();
+                                    });
+                            });
+                    });
+                }
 
                 ui.add_space(5f32);
 
                 ui.horizontal(|ui| {
-                    if ui.button("show").clicked() {
+                    // display interval is applied right away as it does not need new data
+                    if ui.button("show").clicked() || display_changed {
                         let props = TimeRange::parse_props(
                             self.time_start_input.get_time(),
                             self.time_end_input.get_time(),
                             self.date_start,
                             self.date_end,
                             self.interval,
+                            self.display_interval,
                         );
                         match props {
                             Some(props) => {
@@ -217,6 +263,7 @@ impl AppWidget for TimeRange {
                             self.date_start,
                             self.date_end,
                             self.interval,
+                            self.display_interval,
                         );
                         match props {
                             Some(props) => {
@@ -1,8 +1,14 @@
-use chrono::{Date, DateTime, Duration, NaiveTime, Timelike, Utc};
+use chrono::{Date, DateTime, Duration, NaiveDateTime, NaiveTime, Timelike, Utc};
 
-use crate::sources::binance::Interval;
+use crate::sources::{binance::Interval, clock};
 
-use super::bounds::{Bounds, BoundsSet};
+use super::{
+    bounds::{Bounds, BoundsSet},
+    resample::can_resample,
+    step::Step,
+};
+
+const DEFAULT_LIMIT: usize = 1000;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TimeRangeSettings {
@@ -11,7 +17,10 @@ pub struct TimeRangeSettings {
     pub time_start: NaiveTime,
     pub time_end: NaiveTime,
     pub bounds: BoundsSet,
+    /// Interval klines are downloaded with.
     pub interval: Interval,
+    /// Interval klines are shown with. Downloaded klines are resampled to it if it is coarser.
+    pub display_interval: Interval,
     pub limit: usize,
 }
 
@@ -35,19 +44,48 @@ impl TimeRangeSettings {
     pub fn is_valid(&self) -> bool {
         self.start_time() < self.end_time()
     }
-}
 
-impl Default for TimeRangeSettings {
-    fn default() -> Self {
-        let now = Utc::now();
+    /// Bounds cut at exchange time now as there is nothing to load in the future.
+    pub fn loadable_bounds(&self) -> BoundsSet {
+        self.bounds.clip_right(clock::now_millis())
+    }
+
+    /// Interval downloaded klines should be resampled to or None if they are shown as is.
+    pub fn resample_interval(&self) -> Option<Interval> {
+        match can_resample(self.interval, self.display_interval) {
+            true => Some(self.display_interval),
+            false => None,
+        }
+    }
+
+    /// Creates settings ending at exchange time now and starting at least from the previous day.
+    ///
+    /// For coarse intervals range is extended to hold one full page of klines.
+    pub fn for_interval(interval: Interval) -> Self {
+        let now = clock::now();
+
+        let mut start = (now.date() - Duration::days(1)).and_hms(0, 0, 0);
+        let page_start =
+            Step::from(interval).advance(now.timestamp_millis(), -(DEFAULT_LIMIT as i64));
+        if page_start < start.timestamp_millis() {
+            let naive = NaiveDateTime::from_timestamp(page_start / 1000, 0);
+            start = DateTime::from_utc(naive, Utc);
+        }
+
+        TimeRangeSettings::new(start, now, interval)
+    }
+
+    /// Creates settings for the range between start and end truncated to seconds.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, interval: Interval) -> Self {
         let mut p = Self {
-            date_start: now.date() - Duration::days(1),
-            date_end: now.date(),
-            time_start: NaiveTime::from_hms(0, 0, 0),
-            time_end: NaiveTime::from_hms(now.hour(), now.minute(), now.second()),
-            interval: Interval::Minute,
+            date_start: start.date(),
+            date_end: end.date(),
+            time_start: NaiveTime::from_hms(start.hour(), start.minute(), start.second()),
+            time_end: NaiveTime::from_hms(end.hour(), end.minute(), end.second()),
+            interval,
+            display_interval: interval,
             bounds: BoundsSet::default(),
-            limit: 1000,
+            limit: DEFAULT_LIMIT,
         };
 
         p.bounds = BoundsSet::new(vec![Bounds(
@@ -58,3 +96,9 @@ impl Default for TimeRangeSettings {
         p
     }
 }
+
+impl Default for TimeRangeSettings {
+    fn default() -> Self {
+        TimeRangeSettings::for_interval(Interval::Minute)
+    }
+}
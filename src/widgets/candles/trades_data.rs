@@ -0,0 +1,63 @@
+use tracing::debug;
+
+use crate::sources::binance::AggTrade;
+
+#[derive(Default, Clone)]
+pub struct TradesData {
+    pub vals: Vec<AggTrade>,
+    max_qty: f64,
+}
+
+impl TradesData {
+    /// Appends trades skipping already present ones.
+    pub fn append(&mut self, vals: &mut Vec<AggTrade>) {
+        self.vals.append(vals);
+        self.vals.sort_by_key(|el| el.id);
+        self.vals.dedup_by_key(|el| el.id);
+
+        self.max_qty = self.vals.iter().fold(0.0, |acc, el| acc.max(el.qty));
+
+        debug!(
+            "computed trades data props: len: {}, max_qty: {}",
+            self.vals.len(),
+            self.max_qty
+        );
+    }
+
+    pub fn max_qty(&self) -> f64 {
+        self.max_qty
+    }
+}
+
+#[cfg(test)]
+mod trades_data_tests {
+    use super::*;
+
+    #[test]
+    fn test_append() {
+        let mut data = TradesData::default();
+        data.append(&mut vec![
+            AggTrade {
+                id: 2,
+                qty: 1.0,
+                ..Default::default()
+            },
+            AggTrade {
+                id: 1,
+                qty: 3.0,
+                ..Default::default()
+            },
+        ]);
+        data.append(&mut vec![AggTrade {
+            id: 2,
+            qty: 1.0,
+            ..Default::default()
+        }]);
+
+        assert_eq!(
+            data.vals.iter().map(|el| el.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(data.max_qty(), 3.0);
+    }
+}
@@ -0,0 +1,127 @@
+use egui::{
+    plot::{MarkerShape, Plot, Points},
+    Color32,
+};
+
+use crate::{netstrat::Drawer, sources::binance::AggTrade, widgets::AppWidget};
+
+use super::{data::Data, precision::Precision, trades_data::TradesData};
+
+/// Number of marker sizes used to show trade quantity.
+const SIZE_BUCKETS: usize = 5;
+const MIN_RADIUS: f32 = 1.5;
+
+#[derive(Clone)]
+struct Series {
+    taker_buy: bool,
+    size_bucket: usize,
+    vals: Vec<[f64; 2]>,
+}
+
+/// Draws individual trades sized by quantity and coloured by taker side.
+#[derive(Default, Clone)]
+pub struct TradesDrawer {
+    data: TradesData,
+    series: Vec<Series>,
+    precision: Precision,
+}
+
+/// Trades are plotted, not rendered to image, so there is never an image to show.
+impl Drawer for TradesDrawer {
+    fn update_image(&mut self, _image: egui::ColorImage) {}
+
+    fn has_unread_image(&self) -> bool {
+        false
+    }
+}
+
+impl TradesDrawer {
+    pub fn data(&self) -> &TradesData {
+        &self.data
+    }
+
+    pub fn add_data(&mut self, vals: &mut Vec<AggTrade>) {
+        self.data.append(vals);
+
+        let max_qty = self.data.max_qty();
+        let mut series: Vec<Series> = [true, false]
+            .iter()
+            .flat_map(|taker_buy| {
+                (0..SIZE_BUCKETS).map(|size_bucket| Series {
+                    taker_buy: *taker_buy,
+                    size_bucket,
+                    vals: vec![],
+                })
+            })
+            .collect();
+
+        self.data.vals.iter().for_each(|t| {
+            let bucket = TradesDrawer::size_bucket(t.qty, max_qty);
+            let side_offset = if t.taker_buy() { 0 } else { SIZE_BUCKETS };
+            series[side_offset + bucket]
+                .vals
+                .push([t.time as f64, t.price]);
+        });
+
+        series.retain(|s| !s.vals.is_empty());
+        self.series = series;
+    }
+
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision;
+    }
+
+    pub fn clear(&mut self) {
+        self.data = TradesData::default();
+        self.series = vec![];
+    }
+
+    /// Square root scale keeps small trades distinguishable next to the large ones.
+    fn size_bucket(qty: f64, max_qty: f64) -> usize {
+        if max_qty <= 0.0 {
+            return 0;
+        }
+
+        ((qty / max_qty).sqrt() * (SIZE_BUCKETS - 1) as f64).round() as usize
+    }
+}
+
+impl AppWidget for TradesDrawer {
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let precision = self.precision;
+        Plot::new("trades")
+            .label_formatter(move |_, v| -> String {
+                format!("price: {}\n{}", precision.price(v.y), Data::format_ts(v.x))
+            })
+            .x_axis_formatter(|v, _range| Data::format_ts(v))
+            .show(ui, |plot_ui| {
+                self.series.iter().for_each(|s| {
+                    let (color, name) = match s.taker_buy {
+                        true => (Color32::LIGHT_GREEN, "taker buy"),
+                        false => (Color32::LIGHT_RED, "taker sell"),
+                    };
+
+                    plot_ui.points(
+                        Points::new(s.vals.clone())
+                            .shape(MarkerShape::Circle)
+                            .filled(true)
+                            .color(color)
+                            .radius(MIN_RADIUS * (s.size_bucket + 1) as f32)
+                            .name(name),
+                    );
+                });
+            });
+    }
+}
+
+#[cfg(test)]
+mod trades_drawer_tests {
+    use super::*;
+
+    #[test]
+    fn test_size_bucket() {
+        assert_eq!(TradesDrawer::size_bucket(0.0, 10.0), 0);
+        assert_eq!(TradesDrawer::size_bucket(10.0, 10.0), SIZE_BUCKETS - 1);
+        assert_eq!(TradesDrawer::size_bucket(1.0, 0.0), 0);
+    }
+}
@@ -0,0 +1,391 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use egui::{CentralPanel, ProgressBar, TopBottomPanel, Ui};
+use egui_notify::{Anchor, Toasts};
+use tracing::{debug, error, info, trace};
+
+use crate::netstrat::{Drawer, ThreadPool};
+use crate::sources::binance::{
+    errors::ClientError, with_retry, AggTrade, Client, Interval, Symbol,
+};
+use crate::sources::{clock, MarketDataSource};
+use crate::widgets::AppWidget;
+
+use super::error::CandlesError;
+use super::export::write_csv;
+use super::precision::Precision;
+use super::state::State;
+use super::step::Step;
+use super::time_range_settings::TimeRangeSettings;
+use super::trades_drawer::TradesDrawer;
+use super::TimeRange;
+
+const THREAD_POOL_SIZE: usize = 5;
+const PAGE_REQUEST_ATTEMPTS: usize = 5;
+const PAGE_REQUEST_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Max number of trades returned by a single request.
+const TRADES_LIMIT: usize = 1000;
+/// Binance rejects aggregated trades requests with time range longer than an hour.
+const PAGE_MILLIS: i64 = 60 * 60 * 1000;
+const DEFAULT_RANGE_MINUTES: i64 = 15;
+
+/// Downloads, shows and exports aggregated trades for the selected symbol.
+pub struct TradesProps {
+    time_range: Box<dyn AppWidget>,
+
+    trades: TradesDrawer,
+    symbol: String,
+
+    max_frame_pages: usize,
+    data_changed: bool,
+    export_triggered: bool,
+    state: State,
+
+    toasts: Toasts,
+
+    pool: ThreadPool,
+    source: Arc<dyn MarketDataSource>,
+
+    trades_pub: Sender<Result<Vec<AggTrade>, CandlesError>>,
+    trades_sub: Receiver<Result<Vec<AggTrade>, CandlesError>>,
+    drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+    symbol_pub: Sender<String>,
+    symbol_sub: Receiver<Symbol>,
+    props_pub: Sender<TimeRangeSettings>,
+    props_sub: Receiver<TimeRangeSettings>,
+    export_sub: Receiver<TimeRangeSettings>,
+}
+
+impl Default for TradesProps {
+    fn default() -> Self {
+        let (s_symbols, r_symbols) = unbounded();
+        let (s_props, r_props) = unbounded();
+        let (s_props1, r_props1) = unbounded();
+        let (s_export, r_export) = unbounded();
+        let (s_trades, r_trades) = unbounded();
+        let (s_drawer, _) = unbounded();
+        let (_, r_symbol) = unbounded();
+
+        let time_range = Box::new(
+            TimeRange::new(
+                r_symbols,
+                s_props,
+                r_props1,
+                s_export,
+                TradesProps::default_settings(),
+            )
+            .without_interval(),
+        );
+
+        Self {
+            max_frame_pages: 50,
+
+            time_range,
+
+            pool: ThreadPool::new(THREAD_POOL_SIZE),
+            source: Arc::new(Client::default()),
+
+            toasts: Toasts::default().with_anchor(Anchor::TopRight),
+
+            symbol_sub: r_symbol,
+            symbol_pub: s_symbols,
+            drawer_pub: s_drawer,
+            props_sub: r_props,
+            props_pub: s_props1,
+            export_sub: r_export,
+            trades_sub: r_trades,
+            trades_pub: s_trades,
+
+            trades: Default::default(),
+            data_changed: Default::default(),
+            export_triggered: Default::default(),
+            symbol: Default::default(),
+            state: Default::default(),
+        }
+    }
+}
+
+impl TradesProps {
+    pub fn new(
+        source: Arc<dyn MarketDataSource>,
+        symbol_sub: Receiver<Symbol>,
+        drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+    ) -> Self {
+        info!("initing widget trades");
+        Self {
+            source,
+            symbol_sub,
+            drawer_pub,
+            ..Default::default()
+        }
+    }
+
+    /// Trades are dense so by default only the last minutes are loaded.
+    fn default_settings() -> TimeRangeSettings {
+        let now = clock::now();
+        TimeRangeSettings::new(
+            now - chrono::Duration::minutes(DEFAULT_RANGE_MINUTES),
+            now,
+            Interval::Minute,
+        )
+    }
+
+    fn update_data(&mut self, trades: &mut Vec<AggTrade>) {
+        info!("adding {} entries to trades widget", trades.len());
+
+        self.trades.add_data(trades);
+
+        self.data_changed = true;
+    }
+
+    fn start_download(&mut self, props: TimeRangeSettings, reset_state: bool) {
+        if reset_state {
+            self.trades.clear();
+            self.state = State::default();
+        }
+
+        self.state
+            .apply_props_with_step(&props, Step::Millis(PAGE_MILLIS), 1);
+
+        if self.state.loading.pages() == 0 {
+            debug!("trades already downloaded, skipping download");
+            return;
+        }
+
+        debug!(
+            "trades splitted in {} pages; starting download...",
+            self.state.loading.pages()
+        );
+
+        self.perform_data_request();
+    }
+
+    fn perform_data_request(&mut self) {
+        while self.state.loading.get_next_page().is_some() {
+            let start_time = self.state.loading.left_edge();
+            let end_time = self.state.loading.right_edge();
+            let symbol = self.symbol.to_string();
+            let source = self.source.clone();
+
+            let sender = Mutex::new(self.trades_pub.clone());
+            self.pool.execute(move || {
+                debug!("executing trades request: symbol: {symbol}, t_start: {start_time}, t_end: {end_time}");
+                let res = load_page(source.as_ref(), &symbol, start_time, end_time).map_err(|err| {
+                    error!("got trades result with error: {err}");
+                    CandlesError::Error
+                });
+
+                let send_res = sender.lock().unwrap().send(res);
+                if let Err(err) = send_res {
+                    error!("failed to send trades to channel: {err}");
+                };
+            });
+        }
+    }
+
+    fn export_data(&mut self) {
+        debug!("exporting trades");
+
+        let name = format!(
+            "{}_{}_{}_trades.csv",
+            self.symbol,
+            self.state.props.start_time().timestamp(),
+            self.state.props.end_time().timestamp(),
+        );
+
+        let bounds = self.state.props.bounds.clone();
+        let data = &self.trades.data().vals;
+        match write_csv(&name, data.iter().filter(|el| bounds.contains(el.time))) {
+            Ok(abs_path) => {
+                self.toasts
+                    .success("File exported")
+                    .set_duration(Some(Duration::from_secs(3)));
+                info!("exported to file: {abs_path:?}");
+            }
+            Err(err) => {
+                error!("failed to export trades with error: {err}");
+                self.toasts.error("Failed to export trades");
+            }
+        }
+
+        self.export_triggered = false;
+    }
+
+    fn update(&mut self) {
+        let export_wrapped = self.export_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(props) = export_wrapped {
+            debug!("got export msg: {props:?}");
+
+            self.export_triggered = true;
+
+            self.start_download(props, false);
+        }
+
+        let symbol_wrapped = self.symbol_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(symbol) = symbol_wrapped {
+            debug!("got symbol: {}", symbol.symbol);
+
+            self.symbol = symbol.symbol.clone();
+            self.trades.set_precision(Precision::from(&symbol));
+            self.symbol_pub.send(symbol.symbol).unwrap();
+
+            let props = TradesProps::default_settings();
+            if let Err(err) = self.props_pub.send(props.clone()) {
+                error!("failed to send props: {err}");
+            }
+
+            self.start_download(props, true);
+        }
+
+        let show_wrapped = self.props_sub.recv_timeout(Duration::from_millis(1));
+        if let Ok(props) = show_wrapped {
+            debug!("got show button pressed: {props:?}");
+
+            self.start_download(props, true);
+        }
+
+        let mut got = 0;
+        let mut res = vec![];
+        let mut has_error = false;
+        while got < self.max_frame_pages {
+            let package_res = self.trades_sub.recv_timeout(Duration::from_millis(1));
+            if package_res.is_err() {
+                break;
+            }
+
+            match package_res.unwrap() {
+                Ok(mut trades) => res.append(&mut trades),
+                Err(_) => has_error = true,
+            }
+
+            got += 1;
+        }
+
+        if has_error {
+            self.toasts.error("Failed to get trades from Binance");
+        }
+
+        if got > 0 {
+            trace!("received {} pages of trades", got);
+            self.state.loading.inc_loaded_pages(got);
+            self.update_data(&mut res);
+        }
+
+        if self.state.loading.progress() == 1.0 && self.export_triggered {
+            self.export_data();
+        }
+    }
+
+    fn draw_data(&mut self, ui: &Ui) {
+        if self.data_changed {
+            ui.ctx().request_repaint();
+            self.drawer_pub
+                .send(Arc::new(Mutex::new(Box::new(self.trades.clone()))))
+                .unwrap();
+            self.data_changed = false;
+        }
+    }
+}
+
+/// Loads all aggregated trades with time in range from start (included) to end (not included).
+///
+/// Range must be shorter than an hour. Trades which do not fit into a single response
+/// are requested by id of the last received trade.
+fn load_page(
+    source: &dyn MarketDataSource,
+    symbol: &str,
+    start: i64,
+    end: i64,
+) -> Result<Vec<AggTrade>, ClientError> {
+    let mut res = vec![];
+    let mut from_id = None;
+
+    loop {
+        let page = with_retry(PAGE_REQUEST_ATTEMPTS, PAGE_REQUEST_BASE_DELAY, || {
+            source.agg_trades(symbol.to_string(), start, end - 1, from_id, TRADES_LIMIT)
+        })?;
+
+        let full = page.len() == TRADES_LIMIT;
+        let last = page.last().copied();
+        res.extend(page.into_iter().filter(|t| t.time >= start && t.time < end));
+
+        match last {
+            Some(t) if full && t.time < end => from_id = Some(t.id + 1),
+            _ => break,
+        }
+    }
+
+    Ok(res)
+}
+
+impl AppWidget for TradesProps {
+    fn show(&mut self, ui: &mut Ui) {
+        self.update();
+
+        self.draw_data(ui);
+
+        if self.symbol.is_empty() {
+            ui.label("Select a symbol");
+            return;
+        }
+
+        if self.state.loading.progress() < 1.0 {
+            TopBottomPanel::top("trades_toolbar").show_inside(ui, |ui| {
+                ui.add(
+                    ProgressBar::new(self.state.loading.progress())
+                        .show_percentage()
+                        .animate(true),
+                );
+            });
+        }
+
+        CentralPanel::default().show_inside(ui, |ui| {
+            self.time_range.show(ui);
+        });
+
+        self.toasts.show(ui.ctx());
+    }
+}
+
+#[cfg(test)]
+mod trades_props_tests {
+    use crate::sources::FakeSource;
+
+    use super::*;
+
+    /// Serves trades with consequent ids and times, at most limit per call.
+    fn trades_source(trades: Vec<AggTrade>) -> FakeSource {
+        FakeSource::default().with_agg_trades(move |start_time, end_time, from_id, limit| {
+            Ok(trades
+                .iter()
+                .filter(|t| match from_id {
+                    Some(id) => t.id >= id,
+                    None => t.time >= start_time && t.time <= end_time,
+                })
+                .take(limit)
+                .copied()
+                .collect())
+        })
+    }
+
+    #[test]
+    fn test_load_page() {
+        let source = trades_source(
+            (0..2500)
+                .map(|i| AggTrade {
+                    id: i,
+                    time: i,
+                    ..Default::default()
+                })
+                .collect(),
+        );
+
+        let res = load_page(&source, "BTCUSDT", 100, 2200).unwrap();
+
+        assert_eq!(res.len(), 2100);
+        assert_eq!(res.first().unwrap().id, 100);
+        assert_eq!(res.last().unwrap().id, 2199);
+    }
+}
@@ -13,4 +13,4 @@ pub use self::net_props::NetProps;
 pub use self::open_drop_file::OpenDropFile;
 pub use self::simulation_props::{Controls, SimulationProps};
 pub use self::theme::Theme;
-pub use self::widget::AppWidget;
\ No newline at end of file
+pub use self::widget::AppWidget;
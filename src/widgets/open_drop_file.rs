@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use egui::{CursorIcon, FontSelection, Sense, TextEdit, TextStyle};
 use tracing::debug;
 
@@ -5,39 +7,81 @@ use super::AppWidget;
 
 const HINT: &str = "Drop a .dot file here or click to open a file dialog";
 
-#[derive(Default)]
 pub struct OpenDropFile {
-    file_path: Option<String>,
+    file_paths: Vec<String>,
+    hint: &'static str,
+    filter_name: &'static str,
+    extensions: &'static [&'static str],
+    multiple: bool,
+}
+
+impl Default for OpenDropFile {
+    fn default() -> Self {
+        Self::new(HINT, "JSON files", &["json"])
+    }
 }
 
 impl OpenDropFile {
+    pub fn new(
+        hint: &'static str,
+        filter_name: &'static str,
+        extensions: &'static [&'static str],
+    ) -> Self {
+        Self {
+            hint,
+            filter_name,
+            extensions,
+            file_paths: Default::default(),
+            multiple: Default::default(),
+        }
+    }
+
+    /// Accepts several files at once from file dialog or drop.
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    /// Returns the last picked file path.
     pub fn path(&mut self) -> Option<String> {
-        let path = self.file_path.clone();
+        self.paths().pop()
+    }
+
+    /// Returns all picked file paths.
+    pub fn paths(&mut self) -> Vec<String> {
+        let paths = std::mem::take(&mut self.file_paths);
 
-        if self.file_path.is_some() {
-            self.file_path = None;
-            debug!("file path resetted to None");
+        if !paths.is_empty() {
+            debug!("file paths resetted to empty");
         }
 
-        path
+        paths
     }
 
-    fn update(&mut self, file_path: String) {
-        if !file_path.is_empty() {
-            self.file_path = Some(file_path.clone());
-            debug!("file path updated: {file_path:?}");
+    /// Checks that file has one of the extensions the widget opens.
+    fn accepts(&self, path: &Path) -> bool {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => self
+                .extensions
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(ext)),
+            None => false,
+        }
+    }
+
+    fn update(&mut self, file_paths: Vec<String>) {
+        if !file_paths.is_empty() {
+            debug!("file paths updated: {file_paths:?}");
+            self.file_paths = file_paths;
         }
     }
 }
 
 impl AppWidget for OpenDropFile {
     fn show(&mut self, ui: &mut egui::Ui) {
-        let mut file_path = "".to_string();
-        if let Some(path) = self.file_path.clone() {
-            file_path = path;
-        }
+        let mut file_paths = vec![];
 
-        let mut text = HINT.to_string();
+        let mut text = self.hint.to_string();
         if !ui.ctx().input().raw.hovered_files.is_empty() {
             text = format!(
                 "Dropping file: {:?}",
@@ -68,29 +112,50 @@ impl AppWidget for OpenDropFile {
 
         if response.clicked() {
             debug!("opening file dialog");
-            if let Some(opened_path) = rfd::FileDialog::new()
-                .add_filter("JSON files", &["json"])
-                .pick_file()
-            {
-                file_path = opened_path.display().to_string();
-            }
+            let dialog = rfd::FileDialog::new().add_filter(self.filter_name, self.extensions);
+            let opened_paths = match self.multiple {
+                true => dialog.pick_files().unwrap_or_default(),
+                false => dialog.pick_file().into_iter().collect(),
+            };
+            file_paths = opened_paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
         }
 
-        if !ui.ctx().input().raw.dropped_files.is_empty() {
-            file_path = ui
-                .ctx()
-                .input()
-                .raw
-                .dropped_files
-                .last()
-                .unwrap()
-                .path
-                .clone()
-                .unwrap()
-                .display()
-                .to_string();
+        // every visible widget sees the same dropped files so only the one under pointer takes them
+        let pointer_over = matches!(
+            ui.ctx().input().pointer.hover_pos(),
+            Some(pos) if response.rect.contains(pos)
+        );
+        let dropped_files = ui.ctx().input().raw.dropped_files.clone();
+        if !dropped_files.is_empty() && pointer_over {
+            file_paths = dropped_files
+                .iter()
+                .filter_map(|f| f.path.as_ref())
+                .filter(|p| self.accepts(p))
+                .map(|p| p.display().to_string())
+                .collect();
+            if !self.multiple {
+                file_paths = file_paths.pop().into_iter().collect();
+            }
         }
 
-        self.update(file_path);
+        self.update(file_paths);
+    }
+}
+
+#[cfg(test)]
+mod open_drop_file_tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts() {
+        let widget = OpenDropFile::new(HINT, "Klines", &["zip", "csv"]);
+
+        assert!(widget.accepts(Path::new("/tmp/BTCUSDT-1m-2022-01.zip")));
+        assert!(widget.accepts(Path::new("export.CSV")));
+        assert!(!widget.accepts(Path::new("net.json")));
+        assert!(!widget.accepts(Path::new("zip")));
     }
 }
@@ -8,6 +8,7 @@ use tracing::info;
 
 use crate::{
     netstrat::Drawer,
+    sources::MarketDataSource,
     widgets::{
         candles::{Props, Symbols},
         AppWidget,
@@ -54,13 +55,21 @@ impl AppWindow for SymbolsGraph {
 }
 
 impl SymbolsGraph {
-    pub fn new(drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>, visible: bool) -> Self {
+    pub fn new(
+        source: Arc<dyn MarketDataSource>,
+        futures_source: Arc<dyn MarketDataSource>,
+        stream_url: String,
+        futures_stream_url: String,
+        drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+        visible: bool,
+    ) -> Self {
         info!("initing window graph");
 
         let (s, r) = unbounded();
         Self {
-            graph: Props::new(r, drawer_pub),
-            symbols: Symbols::new(s),
+            graph: Props::new(source.clone(), stream_url, r, drawer_pub)
+                .with_futures(futures_source.clone(), futures_stream_url),
+            symbols: Symbols::new(s, source).with_futures(futures_source),
             visible,
         }
     }
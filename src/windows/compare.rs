@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+
+use crossbeam::channel::{unbounded, Sender};
+use eframe::emath::Align;
+use egui::{Layout, Ui, Window};
+use egui_extras::{Size, StripBuilder};
+use tracing::info;
+
+use crate::{
+    netstrat::Drawer,
+    sources::MarketDataSource,
+    widgets::{
+        candles::{CompareProps, Symbols},
+        AppWidget,
+    },
+};
+
+use super::window::AppWindow;
+
+pub struct Compare {
+    compare: CompareProps,
+    symbols: Symbols,
+    visible: bool,
+}
+
+impl AppWindow for Compare {
+    fn toggle_btn(&mut self, ui: &mut Ui) {
+        if ui.button("compare").clicked() {
+            self.update(!self.visible);
+        }
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        let mut visible = self.visible;
+        Window::new("compare")
+            .open(&mut visible)
+            .show(ui.ctx(), |ui| {
+                ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                    StripBuilder::new(ui)
+                        .size(Size::relative(0.25).at_most(200.0))
+                        .size(Size::remainder())
+                        .horizontal(|mut strip| {
+                            strip.cell(|ui| {
+                                self.symbols.show(ui);
+                            });
+                            strip.cell(|ui| {
+                                self.compare.show(ui);
+                            });
+                        })
+                })
+            });
+
+        self.update(visible);
+    }
+}
+
+impl Compare {
+    pub fn new(
+        source: Arc<dyn MarketDataSource>,
+        drawer_pub: Sender<Arc<Mutex<Box<dyn Drawer>>>>,
+        visible: bool,
+    ) -> Self {
+        info!("initing window compare");
+
+        let (s, r) = unbounded();
+        Self {
+            compare: CompareProps::new(source.clone(), r, drawer_pub),
+            symbols: Symbols::new(s, source),
+            visible,
+        }
+    }
+
+    fn update(&mut self, visible: bool) {
+        if visible != self.visible {
+            self.visible = visible;
+            match visible {
+                true => info!("opening compare window..."),
+                false => info!("closing compare window..."),
+            }
+        }
+    }
+}
@@ -6,7 +6,9 @@ use crossbeam::channel::{unbounded, Receiver, Sender};
 use egui::{ScrollArea, TextEdit, Ui, Window};
 use tracing::{info, trace};
 
-use crate::{netstrat::line_filter_highlight_layout, AppWindow};
+use crate::{netstrat::line_filter_highlight_layout, sources::clock};
+
+use super::window::AppWindow;
 
 pub struct BuffWriter {
     pub publisher: Sender<Vec<u8>>,
@@ -147,7 +149,8 @@ impl Debug {
         if filter_normalized.contains(self.filter.as_str()) {
             trace!("using optimized version");
 
-            self.filtered.retain(|el| el.to_lowercase().contains(filter_normalized.as_str()));
+            self.filtered
+                .retain(|el| el.to_lowercase().contains(filter_normalized.as_str()));
 
             self.filter = filter_normalized;
             return;
@@ -185,6 +188,13 @@ impl AppWindow for Debug {
                         .show(ui);
                     ui.label(format!("{}/{}", self.filtered.len(), self.buff.len()));
                     ui.label(format!("{} msgs last minute", self.msgs_last_minute));
+                    ui.label(match clock::last_sync() {
+                        Some(sync) => format!(
+                            "server clock offset: {:+}ms (rtt {}ms)",
+                            sync.offset, sync.rtt
+                        ),
+                        None => "server clock not synced".to_string(),
+                    });
                 });
 
                 ui.add_space(10f32);
@@ -1,11 +1,15 @@
 mod candles;
+mod compare;
 mod debug;
 mod net;
 mod simulator;
+mod trades;
 mod window;
 
 pub use self::candles::SymbolsGraph;
+pub use self::compare::Compare;
 pub use self::debug::{BuffWriter, Debug};
 pub use self::net::Net;
 pub use self::simulator::Simulator;
+pub use self::trades::Trades;
 pub use self::window::AppWindow;
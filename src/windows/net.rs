@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 
-use crate::{netstrat::{Drawer, Bus}, widgets::NetProps};
+use crate::{
+    netstrat::{Bus, Drawer},
+    widgets::NetProps,
+};
 use crossbeam::channel::Sender;
 use egui::{ScrollArea, Ui, Window};
 use tracing::info;